@@ -0,0 +1,240 @@
+use crate::{Decibel, Time};
+
+
+// Kind of level problem that an alarm watches for
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlarmKind {
+    // Level stays above the threshold (e.g. clipping, excessive loudness)
+    OverLevel,
+
+    // Level stays below the threshold (e.g. signal loss)
+    UnderLevel,
+}
+
+// An alarm which trips when a level stays out of bounds for too long
+//
+// This is meant for unattended monitoring. It is not fed with audio samples,
+// but with meter readings (and the JACK time at which they were taken), so it
+// should be updated from the thread that polls the meters, never from the
+// audio thread. This also means that callbacks can do anything they like.
+//
+// To avoid flickering when the level hovers around the threshold, a tripped
+// alarm is only cleared once the level gets back in bounds by some hysteresis
+// margin.
+//
+// Alarms can also be disarmed, in which case they ignore readings. This is
+// useful at startup, when meters read artificially low as they warm up.
+//
+pub struct LevelAlarm {
+    // Kind of level problem that we watch for
+    kind: AlarmKind,
+
+    // Level beyond which the alarm starts to count time
+    threshold: Decibel,
+
+    // Margin by which the level must get back in bounds to clear the alarm
+    hysteresis: Decibel,
+
+    // Time during which the level must stay out of bounds, in µs
+    duration: Time,
+
+    // JACK time at which the level went out of bounds, if it currently is
+    out_of_bounds_since: Option<Time>,
+
+    // Truth that the alarm has tripped
+    tripped: bool,
+
+    // Truth that the alarm takes new readings into account
+    armed: bool,
+
+    // Optional user callback, called with the new alarm state on every change
+    callback: Option<Box<dyn FnMut(AlarmKind, bool)>>,
+}
+
+impl LevelAlarm {
+    // Set up an alarm for a certain kind of problem, threshold, hysteresis
+    // margin and duration (in µs)
+    pub fn new(kind: AlarmKind,
+               threshold: Decibel,
+               hysteresis: Decibel,
+               duration: Time) -> Self {
+        assert!(hysteresis >= 0.0, "Hysteresis margin must be positive");
+        Self {
+            kind,
+            threshold,
+            hysteresis,
+            duration,
+            out_of_bounds_since: None,
+            tripped: false,
+            armed: true,
+            callback: None,
+        }
+    }
+
+    // Call a function whenever the alarm trips (with true) or clears (false)
+    pub fn set_callback(&mut self,
+                        callback: impl FnMut(AlarmKind, bool) + 'static) {
+        self.callback = Some(Box::new(callback));
+    }
+
+    // Arm or disarm the alarm (alarms are armed by default)
+    //
+    // A disarmed alarm ignores readings, so it never trips or clears. Disarm
+    // alarms until the meters which feed them are warmed up, to avoid false
+    // alarms from the incomplete readings that they give at startup. Time
+    // spent out of bounds is only counted from the first reading after the
+    // alarm is armed again.
+    //
+    pub fn set_armed(&mut self, armed: bool) {
+        if !armed { self.out_of_bounds_since = None; }
+        self.armed = armed;
+    }
+
+    // Check if the alarm is armed
+    pub fn is_armed(&self) -> bool {
+        self.armed
+    }
+
+    // Feed a new level reading, taken at a given JACK time, into the alarm
+    pub fn update(&mut self, level: Decibel, time: Time) {
+        if !self.armed { return; }
+
+        // Check if the level is out of bounds, taking hysteresis into account
+        let threshold = if self.tripped {
+            match self.kind {
+                AlarmKind::OverLevel => self.threshold - self.hysteresis,
+                AlarmKind::UnderLevel => self.threshold + self.hysteresis,
+            }
+        } else {
+            self.threshold
+        };
+        let out_of_bounds = match self.kind {
+            AlarmKind::OverLevel => level > threshold,
+            AlarmKind::UnderLevel => level < threshold,
+        };
+
+        // Track how long the level has been out of bounds, and deduce the
+        // new alarm state from that.
+        let tripped = if out_of_bounds {
+            let since = *self.out_of_bounds_since.get_or_insert(time);
+            self.tripped || time.saturating_sub(since) >= self.duration
+        } else {
+            self.out_of_bounds_since = None;
+            false
+        };
+
+        // Notify the user callback of alarm state changes
+        if tripped != self.tripped {
+            self.tripped = tripped;
+            if let Some(callback) = &mut self.callback {
+                callback(self.kind, tripped);
+            }
+        }
+    }
+
+    // Check if the alarm is currently tripped
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    // State changes reported by an alarm's callback
+    type Events = Rc<RefCell<Vec<(AlarmKind, bool)>>>;
+
+    // Build an alarm which records the state changes that it reports
+    fn recording_alarm(kind: AlarmKind) -> (LevelAlarm, Events) {
+        let mut alarm = LevelAlarm::new(kind, -20.0, 3.0, 1_000_000);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorder = events.clone();
+        alarm.set_callback(move |kind, tripped| {
+            recorder.borrow_mut().push((kind, tripped))
+        });
+        (alarm, events)
+    }
+
+    #[test]
+    fn over_level() {
+        let (mut alarm, events) = recording_alarm(AlarmKind::OverLevel);
+
+        // The alarm only trips once the level stayed too high long enough
+        alarm.update(-25.0, 0);
+        alarm.update(-15.0, 500_000);
+        alarm.update(-15.0, 1_000_000);
+        assert!(!alarm.is_tripped());
+        alarm.update(-15.0, 1_500_000);
+        assert!(alarm.is_tripped());
+        assert_eq!(*events.borrow(), vec![(AlarmKind::OverLevel, true)]);
+
+        // Dipping below the threshold restarts the count
+        let (mut restarted, _) = recording_alarm(AlarmKind::OverLevel);
+        restarted.update(-15.0, 0);
+        restarted.update(-25.0, 900_000);
+        restarted.update(-15.0, 1_000_000);
+        restarted.update(-15.0, 1_900_000);
+        assert!(!restarted.is_tripped());
+
+        // Going back under the threshold is not enough to clear it...
+        alarm.update(-21.0, 2_000_000);
+        assert!(alarm.is_tripped());
+
+        // ...the level must get back in bounds by the hysteresis margin
+        alarm.update(-24.0, 2_500_000);
+        assert!(!alarm.is_tripped());
+        assert_eq!(*events.borrow(),
+                   vec![(AlarmKind::OverLevel, true),
+                        (AlarmKind::OverLevel, false)]);
+    }
+
+    #[test]
+    fn under_level() {
+        let (mut alarm, events) = recording_alarm(AlarmKind::UnderLevel);
+
+        // The alarm only trips once the level stayed too low long enough
+        alarm.update(Decibel::NEG_INFINITY, 0);
+        alarm.update(Decibel::NEG_INFINITY, 999_999);
+        assert!(!alarm.is_tripped());
+        alarm.update(Decibel::NEG_INFINITY, 1_000_000);
+        assert!(alarm.is_tripped());
+        assert_eq!(*events.borrow(), vec![(AlarmKind::UnderLevel, true)]);
+
+        // Clearing it takes the threshold plus the hysteresis margin
+        alarm.update(-19.0, 1_500_000);
+        assert!(alarm.is_tripped());
+        alarm.update(-16.0, 2_000_000);
+        assert!(!alarm.is_tripped());
+        assert_eq!(*events.borrow(),
+                   vec![(AlarmKind::UnderLevel, true),
+                        (AlarmKind::UnderLevel, false)]);
+    }
+
+    #[test]
+    fn disarmed() {
+        // While the meters warm up, readings that would trip the alarm are
+        // ignored, however long they last
+        let (mut alarm, events) = recording_alarm(AlarmKind::UnderLevel);
+        alarm.set_armed(false);
+        assert!(!alarm.is_armed());
+        alarm.update(Decibel::NEG_INFINITY, 0);
+        alarm.update(Decibel::NEG_INFINITY, 2_000_000);
+        assert!(!alarm.is_tripped());
+
+        // Once armed, time out of bounds is only counted from the next reading
+        alarm.set_armed(true);
+        alarm.update(Decibel::NEG_INFINITY, 2_500_000);
+        assert!(!alarm.is_tripped());
+        assert!(events.borrow().is_empty());
+        alarm.update(Decibel::NEG_INFINITY, 3_500_000);
+        assert!(alarm.is_tripped());
+
+        // Disarming a tripped alarm freezes it instead of clearing it
+        alarm.set_armed(false);
+        alarm.update(-10.0, 4_000_000);
+        assert!(alarm.is_tripped());
+        assert_eq!(*events.borrow(), vec![(AlarmKind::UnderLevel, true)]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,138 @@
+use crate::{Decibel, Time};
+
+
+// Rate at which a peak display falls back after a peak
+//
+// Peak programme meters (PPMs) are standardized by IEC 60268-10, which
+// specifies their return time as the time needed to fall by a certain amount
+// after a peak. These presets follow the common variants of that standard.
+//
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecayRate {
+    // Type I PPM, German (DIN 45406) variant: 20 dB in 1.5s
+    DinPpm,
+
+    // Type I PPM, Nordic variant: 20 dB in 1.7s
+    NordicPpm,
+
+    // Type II PPM (BBC and EBU variants): 24 dB in 2.8s
+    BbcPpm,
+
+    // Custom decay rate, in dB/s
+    Custom(f32),
+}
+
+impl DecayRate {
+    // Decay rate in dB/s
+    pub fn db_per_second(self) -> f32 {
+        match self {
+            DecayRate::DinPpm => 20.0 / 1.5,
+            DecayRate::NordicPpm => 20.0 / 1.7,
+            DecayRate::BbcPpm => 24.0 / 2.8,
+            DecayRate::Custom(rate) => rate,
+        }
+    }
+}
+
+// A peak value which jumps up instantly, then falls back at a constant rate
+//
+// This is meant to be fed with peak meter readings (and the JACK time at which
+// they were taken) by the thread that polls the meters, in order to produce
+// PPM-style display ballistics. The decay is computed from the elapsed JACK
+// time, so that it does not depend on the polling rate. If the JACK clock ever
+// goes backwards, that update is treated as if no time had elapsed.
+//
+// While JACK is freewheeling, its clock runs much faster than real time, and
+// jumps back to real time when freewheeling stops. The decay is frozen during
+// that time, so that the display neither collapses nor glitches.
+//
+pub struct DecayingPeak {
+    // Decay rate, in dB/µs
+    rate: f32,
+
+    // Current level
+    level: Decibel,
+
+    // JACK time of the last update, if any
+    last_update: Option<Time>,
+}
+
+impl DecayingPeak {
+    // Set up a decaying peak with a certain decay rate
+    pub fn new(rate: DecayRate) -> Self {
+        let rate = rate.db_per_second() / 1_000_000.0;
+        assert!(rate >= 0.0, "Decay rate must be positive");
+        Self {
+            rate,
+            level: Decibel::NEG_INFINITY,
+            last_update: None,
+        }
+    }
+
+    // Feed a new peak reading, taken at a given JACK time, and get the new
+    // value of the decaying peak. Set "freewheeling" if JACK was freewheeling
+    // when the reading was taken (see JackInterface::is_freewheeling()).
+    pub fn update(&mut self,
+                  peak: Decibel,
+                  time: Time,
+                  freewheeling: bool) -> Decibel {
+        let elapsed = self.last_update
+                          .filter(|_| !freewheeling)
+                          .map(|last_update| time.saturating_sub(last_update))
+                          .unwrap_or(0);
+        self.last_update = Some(time);
+        let decayed = self.level - self.rate * (elapsed as f32);
+        self.level = decayed.max(peak);
+        self.level
+    }
+
+    // Query the current value of the decaying peak
+    pub fn read(&self) -> Decibel {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ppm_return_times() {
+        // After a 0 dBFS peak, each preset falls by its nominal amount in its
+        // nominal return time, whatever the polling rate
+        for &(rate, fall, time) in &[(DecayRate::DinPpm, 20.0, 1_500_000),
+                                     (DecayRate::NordicPpm, 20.0, 1_700_000),
+                                     (DecayRate::BbcPpm, 24.0, 2_800_000),
+                                     (DecayRate::Custom(10.0), 20.0, 2_000_000)]
+        {
+            let mut peak = DecayingPeak::new(rate);
+            assert_eq!(peak.update(0.0, 1_000, false), 0.0);
+            let level = peak.update(Decibel::NEG_INFINITY, 1_000 + time, false);
+            assert!((level + fall).abs() < 0.01,
+                    "{:?} fell to {} dB", rate, level);
+
+            let mut polled = DecayingPeak::new(rate);
+            polled.update(0.0, 1_000, false);
+            for t in (1_000..=1_000 + time).step_by(20_000) {
+                polled.update(Decibel::NEG_INFINITY, t, false);
+            }
+            assert!((polled.read() - level).abs() < 0.01,
+                    "{:?} fell to {} dB when polled", rate, polled.read());
+        }
+    }
+
+    #[test]
+    fn freewheeling() {
+        // While JACK is freewheeling, its clock races ahead, but the peak
+        // does not decay
+        let mut peak = DecayingPeak::new(DecayRate::Custom(10.0));
+        assert_eq!(peak.update(0.0, 1_000_000, false), 0.0);
+        assert_eq!(peak.update(-40.0, 2_000_000, true), 0.0);
+        assert_eq!(peak.update(-40.0, 60_000_000, true), 0.0);
+
+        // When freewheeling stops, the clock jumps back to real time, and the
+        // decay resumes from there without any glitch
+        assert_eq!(peak.update(-40.0, 2_500_000, false), 0.0);
+        assert!((peak.update(-40.0, 3_500_000, false) + 10.0).abs() < 0.001);
+    }
+}
\ No newline at end of file
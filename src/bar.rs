@@ -0,0 +1,304 @@
+use crate::Decibel;
+
+
+// How a level in dB maps to the length of a meter bar
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarScale {
+    // Bar length is proportional to the level in dB
+    LinearDb,
+
+    // Bar length follows the IEC 60268-18 meter deflection curve, which gives
+    // more room to the top of the range than to the bottom of it
+    Perceptual,
+}
+
+impl BarScale {
+    // Fraction of the bar lit by a level, given in dB below the top of the
+    // scale, without any clamping (so levels above 0 give fractions above 1)
+    fn deflection(self, level: Decibel, range: Decibel) -> f32 {
+        match self {
+            BarScale::LinearDb => 1.0 + level / range,
+            BarScale::Perceptual => Self::iec_deflection(level),
+        }
+    }
+
+    // IEC 60268-18 deflection curve, piecewise linear in dB, as used by many
+    // software meters. Maps 0 dB to 1 and anything below -70 dB to 0.
+    fn iec_deflection(level: Decibel) -> f32 {
+        let percent = if level < -70.0 {
+            0.0
+        } else if level < -60.0 {
+            (level + 70.0) * 0.25
+        } else if level < -50.0 {
+            (level + 60.0) * 0.5 + 2.5
+        } else if level < -40.0 {
+            (level + 50.0) * 0.75 + 7.5
+        } else if level < -30.0 {
+            (level + 40.0) * 1.5 + 15.0
+        } else if level < -20.0 {
+            (level + 30.0) * 2.0 + 30.0
+        } else {
+            (level + 20.0) * 2.5 + 50.0
+        };
+        percent / 100.0
+    }
+}
+
+// A continuous meter bar, as opposed to the discrete segments of a LedLadder
+//
+// This maps meter readings within a configurable dB range to the fraction of
+// the bar that should be lit, which user interfaces can then scale to their
+// bar size. Readings are meant to go through display ballistics first.
+//
+pub struct Bar {
+    // Mapping from dB to bar length
+    scale: BarScale,
+
+    // Level at the bottom of the bar
+    min: Decibel,
+
+    // Level at the top of the bar
+    max: Decibel,
+}
+
+impl Bar {
+    // Set up a bar with a certain scale, covering levels from min to max dB
+    pub fn new(scale: BarScale, min: Decibel, max: Decibel) -> Self {
+        assert!(min < max, "Bar range must be nonempty");
+        Self { scale, min, max }
+    }
+
+    // Fraction of the bar that is lit by a certain level, between 0 and 1
+    //
+    // Levels below the bottom of the bar (including -inf and NaN) light up
+    // nothing, levels above the top of the bar light up everything.
+    //
+    pub fn fraction(&self, level: Decibel) -> f32 {
+        if level.is_nan() || level <= self.min { return 0.0; }
+        if level >= self.max { return 1.0; }
+        let range = self.max - self.min;
+        let bottom = self.scale.deflection(self.min - self.max, range);
+        let deflection = self.scale.deflection(level - self.max, range);
+        ((deflection - bottom) / (1.0 - bottom)).clamp(0.0, 1.0)
+    }
+
+    // Number of characters/pixels/... lit by a certain level, on a bar which
+    // is "length" units long
+    pub fn lit_length(&self, level: Decibel, length: usize) -> usize {
+        (self.fraction(level) * length as f32).round() as usize
+    }
+
+    // Render the bar as text for a terminal, "length" characters long
+    //
+    // Lit segments are drawn as '=' and unlit ones as ' '. If zones are
+    // specified, lit segments are colored according to the zone of the level
+    // at which they light up, using ANSI escape sequences. Only do this when
+    // the output is a terminal (see Zones::for_terminal()).
+    //
+    pub fn render(&self,
+                  level: Decibel,
+                  length: usize,
+                  zones: Option<&Zones>) -> String {
+        let lit = self.lit_length(level, length);
+        let mut result = String::with_capacity(length);
+        match zones {
+            None => result.push_str(&"=".repeat(lit)),
+            Some(zones) => {
+                // Segments below these indices are in the normal and warning
+                // zones respectively, the others are in the danger zone
+                let warning_start = self.lit_length(zones.warning, length);
+                let danger_start = self.lit_length(zones.danger, length);
+                let mut current_zone = None;
+                for segment in 0..lit {
+                    let zone = if segment < warning_start {
+                        Zone::Normal
+                    } else if segment < danger_start {
+                        Zone::Warning
+                    } else {
+                        Zone::Danger
+                    };
+                    if current_zone != Some(zone) {
+                        result.push_str(zone.ansi_color());
+                        current_zone = Some(zone);
+                    }
+                    result.push('=');
+                }
+                if current_zone.is_some() { result.push_str(ANSI_RESET); }
+            }
+        }
+        result.push_str(&" ".repeat(length - lit));
+        result
+    }
+}
+
+impl Default for Bar {
+    // Use a perceptual bar covering the usual range of a digital meter
+    fn default() -> Self {
+        Self::new(BarScale::Perceptual, -60.0, 0.0)
+    }
+}
+
+// ANSI escape sequence which goes back to the terminal's default color
+const ANSI_RESET: &str = "\x1b[0m";
+
+// Zone of a meter's range, which determines the color it is displayed with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Zone {
+    // Levels which need no attention (displayed in green)
+    Normal,
+
+    // Levels which are getting high (displayed in amber)
+    Warning,
+
+    // Levels which are too high (displayed in red)
+    Danger,
+}
+
+impl Zone {
+    // ANSI escape sequence which switches the terminal to this zone's color
+    pub fn ansi_color(self) -> &'static str {
+        match self {
+            Zone::Normal => "\x1b[32m",
+            Zone::Warning => "\x1b[33m",
+            Zone::Danger => "\x1b[31m",
+        }
+    }
+}
+
+// Level thresholds which split a meter's range into zones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zones {
+    // Level from which the warning zone starts
+    warning: Decibel,
+
+    // Level from which the danger zone starts
+    danger: Decibel,
+}
+
+impl Zones {
+    // Set up zones with custom thresholds, in the unit of the meter readings
+    pub fn new(warning: Decibel, danger: Decibel) -> Self {
+        assert!(warning <= danger,
+                "Warning zone must start below the danger zone");
+        Self { warning, danger }
+    }
+
+    // Zone that a certain level falls into
+    pub fn zone(&self, level: Decibel) -> Zone {
+        if level >= self.danger {
+            Zone::Danger
+        } else if level >= self.warning {
+            Zone::Warning
+        } else {
+            Zone::Normal
+        }
+    }
+
+    // Default zones, if the standard error stream is a terminal which can
+    // display colors, otherwise None (for use with Bar::render())
+    pub fn for_terminal() -> Option<Self> {
+        use std::io::IsTerminal;
+        if std::io::stderr().is_terminal()
+           && std::env::var_os("NO_COLOR").is_none()
+        {
+            Some(Self::default())
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Zones {
+    // Warn from -18 dB (the usual alignment level of digital audio), and
+    // flag danger from -9 dB, like many digital peak meters do
+    fn default() -> Self {
+        Self::new(-18.0, -9.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Check that a fraction is within rounding error of the expected value
+    fn assert_fraction(bar: &Bar, level: Decibel, expected: f32) {
+        let fraction = bar.fraction(level);
+        assert!((fraction - expected).abs() < 1e-5,
+                "{} dB lit {} of the bar, expected {}",
+                level, fraction, expected);
+    }
+
+    #[test]
+    fn linear_db_fraction() {
+        let bar = Bar::new(BarScale::LinearDb, -60.0, 0.0);
+        assert_fraction(&bar, -60.0, 0.0);
+        assert_fraction(&bar, -45.0, 0.25);
+        assert_fraction(&bar, -30.0, 0.5);
+        assert_fraction(&bar, -6.0, 0.9);
+        assert_fraction(&bar, 0.0, 1.0);
+
+        // Out-of-range levels are clamped
+        assert_fraction(&bar, -80.0, 0.0);
+        assert_fraction(&bar, Decibel::NEG_INFINITY, 0.0);
+        assert_fraction(&bar, Decibel::NAN, 0.0);
+        assert_fraction(&bar, 3.0, 1.0);
+    }
+
+    #[test]
+    fn perceptual_fraction() {
+        // Over the full range of the IEC curve, fractions follow it exactly
+        let bar = Bar::new(BarScale::Perceptual, -70.0, 0.0);
+        assert_fraction(&bar, -65.0, 0.0125);
+        assert_fraction(&bar, -60.0, 0.025);
+        assert_fraction(&bar, -50.0, 0.075);
+        assert_fraction(&bar, -40.0, 0.15);
+        assert_fraction(&bar, -30.0, 0.3);
+        assert_fraction(&bar, -20.0, 0.5);
+        assert_fraction(&bar, -10.0, 0.75);
+        assert_fraction(&bar, 0.0, 1.0);
+
+        // Over a shorter range, the curve is rescaled to fill the bar
+        let bar = Bar::default();
+        assert_fraction(&bar, -60.0, 0.0);
+        assert_fraction(&bar, -20.0, 0.475 / 0.975);
+        assert_fraction(&bar, 0.0, 1.0);
+        assert_fraction(&bar, Decibel::NAN, 0.0);
+        assert_fraction(&bar, 3.0, 1.0);
+    }
+
+    #[test]
+    fn zones() {
+        let zones = Zones::default();
+        assert_eq!(zones.zone(Decibel::NEG_INFINITY), Zone::Normal);
+        assert_eq!(zones.zone(-18.1), Zone::Normal);
+        assert_eq!(zones.zone(-18.0), Zone::Warning);
+        assert_eq!(zones.zone(-9.1), Zone::Warning);
+        assert_eq!(zones.zone(-9.0), Zone::Danger);
+        assert_eq!(zones.zone(3.0), Zone::Danger);
+    }
+
+    #[test]
+    fn render() {
+        // With 5 dB per character, the warning zone starts at character 8
+        // (-18 dB rounds to 8.4 characters) and the danger zone at 10
+        let bar = Bar::new(BarScale::LinearDb, -60.0, 0.0);
+        let zones = Zones::default();
+        let (normal, warning, danger) = (Zone::Normal.ansi_color(),
+                                         Zone::Warning.ansi_color(),
+                                         Zone::Danger.ansi_color());
+
+        // Without zones, there are no escape sequences
+        assert_eq!(bar.render(-30.0, 12, None), "======      ");
+        assert_eq!(bar.render(Decibel::NEG_INFINITY, 12, Some(&zones)),
+                   " ".repeat(12));
+
+        // With zones, each zone is colored, and colors are reset at the end
+        assert_eq!(bar.render(-30.0, 12, Some(&zones)),
+                   format!("{}======{}      ", normal, ANSI_RESET));
+        assert_eq!(bar.render(-10.0, 12, Some(&zones)),
+                   format!("{}========{}=={}  ", normal, warning, ANSI_RESET));
+        assert_eq!(bar.render(0.0, 12, Some(&zones)),
+                   format!("{}========{}=={}=={}",
+                           normal, warning, danger, ANSI_RESET));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,154 @@
+use crate::{
+    Decibel,
+    Sample,
+    meters::{
+        IntegratedRmsMeter,
+        Meter,
+        MeterConfig,
+        QuasiPeakMeter,
+        RmsMeter,
+        SamplePeakMeter,
+        VUMeter,
+        ZeroCrossingMeter,
+    },
+};
+
+
+// Frequency of the calibration tone, in Hz
+const TONE_FREQUENCY: f64 = 1000.0;
+
+// Amplitude of the calibration tone, in dBFS
+const TONE_LEVEL: Decibel = -20.0;
+
+// Duration of the calibration tone, in seconds (long enough for every meter's
+// ballistics to settle)
+const TONE_DURATION: f64 = 2.0;
+
+// Maximal acceptable deviation from the expected reading, in dB
+const TOLERANCE: Decibel = 0.1;
+
+// Maximal acceptable deviation from the expected zero-crossing rate, in
+// crossings per second (1% of the expected rate)
+const ZCR_TOLERANCE: f32 = 0.02 * TONE_FREQUENCY as f32;
+
+// Reading of a quasi-peak meter with the default attack time on the
+// calibration tone, relative to its amplitude, in dB. The envelope only rises
+// during part of each waveform period, so it never quite reaches the peaks.
+const QUASI_PEAK_OFFSET: Decibel = -0.27;
+
+// Outcome of checking one meter against the calibration tone
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationCheck {
+    // Name of the meter that was checked
+    pub meter: &'static str,
+
+    // Unit of the meter's readings, e.g. "dB"
+    pub unit: &'static str,
+
+    // Reading that the meter should have produced
+    pub expected: f32,
+
+    // Reading that the meter actually produced
+    pub measured: f32,
+
+    // Maximal acceptable deviation from the expected reading
+    pub tolerance: f32,
+}
+
+impl CalibrationCheck {
+    // Truth that the meter read the calibration tone correctly
+    pub fn passed(&self) -> bool {
+        (self.measured - self.expected).abs() <= self.tolerance
+    }
+}
+
+// Check that every meter reads a known calibration tone correctly
+//
+// A sine wave at 1 kHz and -20 dBFS is generated internally and fed into
+// freshly built meters with default settings, so this validates the metering
+// algorithms independently of any audio hardware or JACK connection. Do not
+// call this from the audio thread, it allocates and takes some time.
+//
+pub fn self_test(sampling_rate: u32) -> Vec<CalibrationCheck> {
+    // Generate the calibration tone
+    let amplitude = 10.0f64.powf(TONE_LEVEL as f64 / 20.0);
+    let omega = 2.0 * std::f64::consts::PI * TONE_FREQUENCY
+                / (sampling_rate as f64);
+    let num_samples = (TONE_DURATION * sampling_rate as f64) as usize;
+    let tone = (0..num_samples).map(|i| (amplitude * (omega * i as f64).sin())
+                                           as Sample)
+                               .collect::<Vec<_>>();
+
+    // The RMS level of a sine is 3 dB below its amplitude
+    let rms_level = TONE_LEVEL + 10.0 * 0.5f32.log10();
+
+    // Feed it into every meter and check the readings
+    vec![
+        check::<SamplePeakMeter>("sample peak", &tone, sampling_rate,
+                                 TONE_LEVEL),
+        check::<QuasiPeakMeter>("quasi-peak", &tone, sampling_rate,
+                                TONE_LEVEL + QUASI_PEAK_OFFSET),
+        check::<VUMeter>("VU", &tone, sampling_rate, TONE_LEVEL),
+        check::<RmsMeter>("RMS", &tone, sampling_rate, rms_level),
+        check::<IntegratedRmsMeter>("integrated RMS", &tone, sampling_rate,
+                                    rms_level),
+        // There are two zero crossings per waveform period
+        check::<ZeroCrossingMeter>("zero-crossing rate", &tone, sampling_rate,
+                                   2.0 * TONE_FREQUENCY as f32),
+    ]
+}
+
+// Feed the calibration tone into a new meter and check its reading
+fn check<M: Meter>(meter: &'static str,
+                   tone: &[Sample],
+                   sampling_rate: u32,
+                   expected: f32) -> CalibrationCheck {
+    let instance = M::new_for(sampling_rate);
+    instance.integrate_slice(tone);
+    let (unit, tolerance) = match instance.config() {
+        MeterConfig::ZeroCrossing { .. } => ("crossings/s", ZCR_TOLERANCE),
+        _ => ("dB", TOLERANCE),
+    };
+    CalibrationCheck {
+        meter,
+        unit,
+        expected,
+        measured: instance.read(),
+        tolerance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_passes() {
+        // Every meter reads the calibration tone correctly at common sampling
+        // rates, which also validates their sampling rate dependent settings
+        for &sampling_rate in &[44_100, 48_000, 96_000, 192_000] {
+            let checks = self_test(sampling_rate);
+            assert_eq!(checks.len(), 6);
+            for check in checks {
+                assert!(check.passed(),
+                        "{} meter read {} {} at {} Hz, expected {} {}",
+                        check.meter, check.measured, check.unit,
+                        sampling_rate, check.expected, check.unit);
+            }
+        }
+    }
+
+    #[test]
+    fn failed_check() {
+        // A reading outside of the tolerance is reported as a failure
+        let check = CalibrationCheck {
+            meter: "test",
+            unit: "dB",
+            expected: -20.0,
+            measured: -20.2,
+            tolerance: TOLERANCE,
+        };
+        assert!(!check.passed());
+        assert!(CalibrationCheck { measured: -20.05, ..check }.passed());
+    }
+}
\ No newline at end of file
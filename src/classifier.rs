@@ -0,0 +1,142 @@
+use crate::Decibel;
+
+
+// Rough nature of the signal on the input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignalClass {
+    // Nothing worth mentioning (e.g. disconnected input, noise floor)
+    Silence,
+
+    // Steady periodic signal with a low crest factor (e.g. sine test tone)
+    Tone,
+
+    // Noise-like signal, which crosses zero very often (e.g. white noise)
+    Noise,
+
+    // Anything else, presumably actual program material (music, speech...)
+    Program,
+}
+
+// A lightweight classifier which tells what kind of signal is on the input
+//
+// This is meant for automated test rigs, which want to label their input. It
+// is fed with meter readings by the thread that polls the meters: a peak
+// reading, an RMS reading, and a zero-crossing rate. From the first two, it
+// computes the crest factor (peak to RMS ratio), which is 3 dB for a sine
+// wave and much higher for noise and program material. The zero-crossing rate
+// then separates noise, which crosses zero at a good fraction of the sampling
+// rate, from program material.
+//
+// For the crest factor to be meaningful, the peak and RMS readings should
+// cover similar periods of time.
+//
+pub struct SignalClassifier {
+    // RMS level below which the signal is considered silent, in dBFS
+    silence_threshold: Decibel,
+
+    // Crest factor below which a signal is considered to be a tone, in dB
+    max_tone_crest: Decibel,
+
+    // Zero-crossing rate above which a signal is considered noise, in Hz
+    min_noise_zcr: f32,
+}
+
+impl SignalClassifier {
+    // Set up a signal classifier with custom thresholds
+    pub fn new(silence_threshold: Decibel,
+               max_tone_crest: Decibel,
+               min_noise_zcr: f32) -> Self {
+        assert!(max_tone_crest >= 0.0, "Crest factors are always positive");
+        assert!(min_noise_zcr >= 0.0, "Zero-crossing rates are positive");
+        Self {
+            silence_threshold,
+            max_tone_crest,
+            min_noise_zcr,
+        }
+    }
+
+    // Classify a signal, given its peak level (dBFS), RMS level (dBFS) and
+    // zero-crossing rate (Hz)
+    pub fn classify(&self,
+                    peak: Decibel,
+                    rms: Decibel,
+                    zero_crossing_rate: f32) -> SignalClass {
+        if rms.is_nan() || rms < self.silence_threshold {
+            return SignalClass::Silence;
+        }
+        let crest = peak - rms;
+        if crest <= self.max_tone_crest {
+            SignalClass::Tone
+        } else if zero_crossing_rate >= self.min_noise_zcr {
+            SignalClass::Noise
+        } else {
+            SignalClass::Program
+        }
+    }
+}
+
+impl Default for SignalClassifier {
+    // Silence below -60 dBFS, tones up to 1 dB above a sine's crest factor,
+    // noise above 5 kHz of zero crossings (white noise gets half the sampling
+    // rate, and program material rarely gets anywhere close)
+    fn default() -> Self {
+        Self::new(-60.0, 4.0, 5000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Sample,
+        meters::{RmsMeter, SamplePeakMeter, ZeroCrossingMeter},
+    };
+
+    // Sampling rate used by the tests
+    const SAMPLING_RATE: u32 = 48_000;
+
+    // Classify one second of audio, using the same meters as dbmeter
+    fn classify(signal: impl Fn(usize) -> Sample) -> SignalClass {
+        let audio = (0..SAMPLING_RATE as usize).map(signal)
+                                               .collect::<Vec<_>>();
+        let peak = SamplePeakMeter::new();
+        peak.integrate(audio.iter().copied());
+        let rms = RmsMeter::new(SAMPLING_RATE, Default::default());
+        rms.integrate(audio.iter().copied());
+        let zcr = ZeroCrossingMeter::new(SAMPLING_RATE);
+        zcr.integrate(audio.iter().copied());
+        SignalClassifier::default().classify(peak.read_and_reset(),
+                                             rms.read(),
+                                             zcr.rate_hz())
+    }
+
+    // Uniform white noise of a given amplitude, from a xorshift generator so
+    // that the tests are reproducible
+    fn white_noise(amplitude: Sample) -> impl Fn(usize) -> Sample {
+        move |i| {
+            let mut x = (i as u32).wrapping_mul(2_654_435_761) | 1;
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            amplitude * (2.0 * (x as Sample / u32::MAX as Sample) - 1.0)
+        }
+    }
+
+    #[test]
+    fn tone() {
+        let omega = 2.0 * std::f32::consts::PI * 1000.0 / SAMPLING_RATE as f32;
+        assert_eq!(classify(|i| 0.1 * (omega * i as f32).sin()),
+                   SignalClass::Tone);
+    }
+
+    #[test]
+    fn noise() {
+        assert_eq!(classify(white_noise(0.5)), SignalClass::Noise);
+    }
+
+    #[test]
+    fn silence() {
+        assert_eq!(classify(|_| 0.0), SignalClass::Silence);
+        assert_eq!(classify(white_noise(1e-4)), SignalClass::Silence);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,89 @@
+use std::fmt;
+
+
+// Errors which can be reported by this crate
+#[derive(Debug)]
+pub enum Error {
+    // Something went wrong on the JACK side
+    #[cfg(feature = "jack-backend")]
+    Jack(::jack::Error),
+
+    // The JACK server is not running, and we were not allowed to start it
+    #[cfg(feature = "jack-backend")]
+    ServerNotRunning,
+
+    // The user asked for a configuration that does not make sense
+    InvalidConfig(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "jack-backend")]
+            Error::Jack(e) => write!(f, "JACK error: {}", e),
+            #[cfg(feature = "jack-backend")]
+            Error::ServerNotRunning => {
+                write!(f, "The JACK server is not running")
+            }
+            Error::InvalidConfig(why) => {
+                write!(f, "Invalid configuration: {}", why)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "jack-backend")]
+            Error::Jack(e) => Some(e),
+            #[cfg(feature = "jack-backend")]
+            Error::ServerNotRunning => None,
+            Error::InvalidConfig(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "jack-backend")]
+impl From<::jack::Error> for Error {
+    fn from(e: ::jack::Error) -> Self {
+        Error::Jack(e)
+    }
+}
+
+// Results of fallible operations from this crate
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn invalid_config() {
+        let error = Error::InvalidConfig("Publication period must be nonzero");
+        assert_eq!(error.to_string(),
+                   "Invalid configuration: Publication period must be nonzero");
+        assert!(error.source().is_none());
+    }
+
+    #[cfg(feature = "jack-backend")]
+    #[test]
+    fn jack() {
+        let status = ::jack::ClientStatus::SERVER_FAILED;
+        let error: Error = ::jack::Error::ClientError(status).into();
+        let jack_error = ::jack::Error::ClientError(status).to_string();
+        assert_eq!(error.to_string(), format!("JACK error: {}", jack_error));
+        let source = error.source().expect("JACK errors should have a source");
+        assert_eq!(source.to_string(), jack_error);
+        assert!(source.downcast_ref::<::jack::Error>().is_some());
+    }
+
+    #[cfg(feature = "jack-backend")]
+    #[test]
+    fn server_not_running() {
+        let error = Error::ServerNotRunning;
+        assert_eq!(error.to_string(), "The JACK server is not running");
+        assert!(error.source().is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,223 @@
+use crate::{Decibel, Sample};
+
+
+// Kind of test signal that SignalGenerator can produce
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TestSignal {
+    // 1 kHz sine wave
+    Tone,
+
+    // Pink noise, which has equal energy per octave like program material
+    PinkNoise,
+}
+
+// A generator of calibrated test signals
+//
+// This produces an endless stream of test signal samples at a given RMS
+// level, which can be fed into the meters instead of actual audio input in
+// order to validate a live setup. It does not allocate, and is cheap enough to
+// run on the audio thread.
+//
+#[derive(Clone)]
+pub struct SignalGenerator {
+    // Kind of signal being generated
+    signal: TestSignal,
+
+    // RMS level of the generated signal, in dBFS
+    rms_level: Decibel,
+
+    // Sampling rate that the generator is configured for
+    sampling_rate: u32,
+
+    // Factor by which the raw signal (sine or filtered white noise) must be
+    // multiplied to get the requested RMS level
+    gain: f32,
+
+    // Current phase of the sine wave, in radians
+    phase: f64,
+
+    // Phase increment of the sine wave between two samples, in radians
+    phase_increment: f64,
+
+    // State of the pseudo-random white noise generator
+    rng_state: u32,
+
+    // State of the pink noise filter
+    pink_state: [f32; 3],
+}
+
+impl SignalGenerator {
+    // Frequency of the tone, in Hz
+    pub const TONE_FREQUENCY: f64 = 1000.0;
+
+    // Pink noise filter poles and gains (Paul Kellet's "economy" filter). The
+    // output is a sum of first-order recursive filters, the last of which has
+    // a pole at 0 since it is the white noise input itself.
+    const PINK_POLES: [f32; 4] = [0.99765, 0.96300, 0.57000, 0.0];
+    const PINK_GAINS: [f32; 4] = [0.0990460, 0.2965164, 1.0526913, 0.1848];
+
+    // Set up a generator for a certain signal, RMS level and sampling rate
+    pub fn new(signal: TestSignal,
+               rms_level: Decibel,
+               sampling_rate: u32) -> Self {
+        let mut result = Self {
+            signal,
+            rms_level,
+            sampling_rate,
+            gain: 0.0,
+            phase: 0.0,
+            phase_increment: 0.0,
+            rng_state: 0x1234_5678,
+            pink_state: [0.0; 3],
+        };
+        result.update_coefficients();
+        result
+    }
+
+    // Change the signal, RMS level or sampling rate, without resetting the
+    // generator's internal state (so that there is no glitch). This is cheap
+    // when nothing changes, so it can be called on every audio buffer.
+    pub fn configure(&mut self,
+                     signal: TestSignal,
+                     rms_level: Decibel,
+                     sampling_rate: u32) {
+        if (signal, rms_level, sampling_rate)
+           == (self.signal, self.rms_level, self.sampling_rate)
+        {
+            return;
+        }
+        self.signal = signal;
+        self.rms_level = rms_level;
+        self.sampling_rate = sampling_rate;
+        self.update_coefficients();
+    }
+
+    // Recompute the generator's coefficients after a configuration change
+    fn update_coefficients(&mut self) {
+        let rms = 10.0f64.powf(self.rms_level as f64 / 20.0);
+        self.gain = match self.signal {
+            // The RMS of a sine is its amplitude divided by sqrt(2)...
+            TestSignal::Tone => rms * std::f64::consts::SQRT_2,
+            // ...and that of our pink noise is the RMS of the uniform white
+            // noise that we feed in, 1/sqrt(3), times the RMS gain of the pink
+            // noise filter.
+            TestSignal::PinkNoise => {
+                rms * 3.0f64.sqrt() / Self::pink_rms_gain()
+            }
+        } as f32;
+        self.phase_increment = 2.0 * std::f64::consts::PI
+                               * Self::TONE_FREQUENCY
+                               / (self.sampling_rate as f64);
+    }
+
+    // RMS gain of the pink noise filter for white noise input
+    //
+    // All first-order filters are fed with the same input. For unit variance
+    // white noise, the covariance of the outputs of two such filters with
+    // poles a and b and gains c and d is c * d / (1 - a * b), and the variance
+    // of the sum of the outputs is the sum of all those covariances.
+    //
+    fn pink_rms_gain() -> f64 {
+        let mut variance = 0.0;
+        for (&a, &c) in Self::PINK_POLES.iter().zip(Self::PINK_GAINS.iter()) {
+            for (&b, &d) in Self::PINK_POLES.iter()
+                                           .zip(Self::PINK_GAINS.iter())
+            {
+                let (a, b, c, d) = (a as f64, b as f64, c as f64, d as f64);
+                variance += c * d / (1.0 - a * b);
+            }
+        }
+        variance.sqrt()
+    }
+
+    // Fill a buffer with the next samples of the test signal
+    pub fn fill(&mut self, buffer: &mut [Sample]) {
+        for (dst, spl) in buffer.iter_mut().zip(self) {
+            *dst = spl;
+        }
+    }
+
+    // Generate uniform white noise between -1 and 1 (xorshift32)
+    fn white_noise(&mut self) -> f32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng_state = x;
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+impl Iterator for SignalGenerator {
+    type Item = Sample;
+
+    // The signal never ends, so this always returns Some
+    fn next(&mut self) -> Option<Sample> {
+        let raw = match self.signal {
+            TestSignal::Tone => {
+                let raw = self.phase.sin() as f32;
+                self.phase = (self.phase + self.phase_increment)
+                             % (2.0 * std::f64::consts::PI);
+                raw
+            }
+            TestSignal::PinkNoise => {
+                let white = self.white_noise();
+                let mut pink = white * Self::PINK_GAINS[3];
+                for ((state, &pole), &gain) in
+                    self.pink_state.iter_mut()
+                                   .zip(Self::PINK_POLES.iter())
+                                   .zip(Self::PINK_GAINS.iter())
+                {
+                    *state = pole * *state + white * gain;
+                    pink += *state;
+                }
+                pink
+            }
+        };
+        Some(raw * self.gain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RMS level of some samples, in dBFS
+    fn rms_level(samples: &[Sample]) -> Decibel {
+        let sum_squares = samples.iter()
+                                 .map(|&x| (x as f64) * (x as f64))
+                                 .sum::<f64>();
+        (10.0 * (sum_squares / samples.len() as f64).log10()) as Decibel
+    }
+
+    // Generate 10 seconds of a test signal at -20 dBFS RMS and 48 kHz
+    fn generate(signal: TestSignal) -> Vec<Sample> {
+        let mut buffer = vec![0.0; 480_000];
+        SignalGenerator::new(signal, -20.0, 48_000).fill(&mut buffer);
+        buffer
+    }
+
+    #[test]
+    fn tone_level() {
+        assert!((rms_level(&generate(TestSignal::Tone)) + 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn pink_noise_level() {
+        let level = rms_level(&generate(TestSignal::PinkNoise));
+        assert!((level + 20.0).abs() < 0.2, "Pink noise read {} dBFS", level);
+    }
+
+    #[test]
+    fn reconfiguration_is_seamless() {
+        // Changing the level of a tone should not reset its phase
+        let mut generator =
+            SignalGenerator::new(TestSignal::Tone, -20.0, 48_000);
+        let mut reference = generator.clone();
+        generator.nth(9);
+        reference.nth(9);
+        generator.configure(TestSignal::Tone, -26.0, 48_000);
+        let ratio = generator.next().unwrap() / reference.next().unwrap();
+        assert!((20.0 * ratio.log10() + 6.0).abs() < 0.01);
+    }
+}
\ No newline at end of file
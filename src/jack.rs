@@ -1,6 +1,23 @@
 use crate::{
     Decibel,
-    meters::{SamplePeakMeter, VUMeter},
+    Sample,
+    error::{Error, Result},
+    generator::{SignalGenerator, TestSignal},
+    meters::{
+        IntegratedRmsBatch,
+        IntegratedRmsMeter,
+        Meter,
+        QuasiPeakMeter,
+        QuasiPeakState,
+        RmsMeter,
+        RmsState,
+        SamplePeakMeter,
+        VUMeter,
+        VUState,
+        ZeroCrossingMeter,
+        ZeroCrossingState,
+    },
+    units,
 };
 
 use ::jack::{
@@ -13,6 +30,7 @@ use ::jack::{
     Frames,
     NotificationHandler,
     Port,
+    PortFlags,
     ProcessHandler,
     ProcessScope,
     Time,
@@ -22,14 +40,52 @@ use std::{
     panic,
     sync::{
         Arc,
-        atomic::{AtomicBool, AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     },
+    time::{Duration, Instant},
 };
 
 
 // Let's just hardcode JACK names, they don't really matter do they?
-const CLIENT_NAME: &'static str = "dbmeter";
-const PORT_NAME: &'static str = "in";
+const CLIENT_NAME: &str = "dbmeter";
+const PORT_NAME: &str = "in";
+
+// This one, however, is mandated by the JACK API
+const AUDIO_TYPE: &str = "32 bit float mono audio";
+
+// How long meter readings are considered suspicious after an xrun, in µs. This
+// is about the time it takes for the slowest meter ballistics to settle.
+const XRUN_RECOVERY_TIME: Time = 1_000_000;
+
+// How much audio must be fed into the meters at startup before they give
+// meaningful readings, in seconds. The slowest meter to fill up is the
+// zero-crossing meter, whose 300ms time constant gets within 1% of its final
+// value in about 1.4s. The others need 300ms (VU), 580ms (RMS, "Fast" time
+// weighting) and 400ms (first integrated RMS block).
+const WARMUP_TIME: f64 = 1.5;
+
+// Value of JackState::last_xrun when no xrun has occurred yet
+const NO_XRUN: Time = Time::MAX;
+
+// Value of JackState::test_signal when no test signal should be generated
+const NO_TEST_SIGNAL: u64 = 0;
+
+// Value of JackState::max_peak when no peak was recorded yet
+const NO_MAX_PEAK: u64 = 0;
+
+// Number of low-order bits of JackState::max_peak which hold the timestamp of
+// the peak, and the corresponding mask. This is enough for about 50 days.
+const MAX_PEAK_TIME_BITS: u32 = 42;
+const MAX_PEAK_TIME_MASK: u64 = (1 << MAX_PEAK_TIME_BITS) - 1;
+
+// Flags of JackState::pending_resets, one per kind of meter reset that other
+// threads can ask the audio thread to carry out
+const RESET_INTEGRATED_RMS: u32 = 1 << 0;
+const RESET_SESSION: u32 = 1 << 1;
+
+// How often threads which wait for the audio thread to carry out a meter
+// reset check if it is done
+const RESET_POLL_PERIOD: Duration = Duration::from_millis(1);
 
 
 // This struct is shared between JACK threads and the rest of the world...
@@ -37,32 +93,204 @@ struct JackState {
     // Truth that the audio thread is alive
     alive: AtomicBool,
 
-    // Access to the audio input port
-    input_port: Port<AudioIn>,
+    // Truth that metering is paused (meters then keep their last values)
+    paused: AtomicBool,
+
+    // Truth that JACK is freewheeling, i.e. that its clock is not real time
+    freewheeling: AtomicBool,
+
+    // Sampling rate that the meters are configured for, in Hz. This is only
+    // updated once all meters have been reconfigured.
+    sampling_rate: AtomicU32,
+
+    // Jack clock timestamp at which this state was set up
+    start_time: Time,
 
     // Jack clock timestamp as of the end of the last processed frame
     next_time: AtomicU64,
 
+    // Number of audio frames that were fed into the meters
+    frames_processed: AtomicU64,
+
+    // Value of frames_processed when the last measurement session started
+    session_start_frames: AtomicU64,
+
+    // Jack clock timestamp of the last xrun, or NO_XRUN if there was none
+    last_xrun: AtomicU64,
+
+    // Number of xruns since startup
+    xruns: AtomicU64,
+
+    // Test signal which should be fed into the meters instead of the audio
+    // input, if any. The kind of signal is in the high-order 32 bits (see
+    // encode_test_signal()), and the bits of the RMS level (an FP number of
+    // dBFS) are in the low-order bits, so that both can be updated at once.
+    test_signal: AtomicU64,
+
+    // Peak of the last processed audio buffer, as the bits of an FP sample
+    last_buffer_peak: AtomicU32,
+
+    // Highest sample since startup or the last peak hold reset, along with
+    // the JACK clock timestamp at which it occurred. The high-order bits of
+    // the sample are in the high-order bits (see encode_max_peak()), and the
+    // timestamp (relative to start_time) is in the low-order bits, so that
+    // both can be updated at once. This is the source of truth for the held
+    // peak, which the peak meter also tracks but we don't use.
+    max_peak: AtomicU64,
+
+    // Moving average of the process() callback duration, as the bits of an FP
+    // number of microseconds
+    process_time_avg: AtomicU32,
+
+    // Maximal process() callback duration since the last query, as the bits of
+    // an FP number of microseconds
+    process_time_max: AtomicU32,
+
+    // Meter resets which were requested by other threads, but not carried out
+    // by the audio thread yet (see the RESET_XYZ flags). Resets must go
+    // through the audio thread, as it would otherwise overwrite them when
+    // publishing meter updates that it accumulated beforehand.
+    pending_resets: AtomicU32,
+
     // Peak metering
     peak_meter: SamplePeakMeter,
 
+    // Peak metering with a short attack time, for displays
+    quasi_peak_meter: QuasiPeakMeter,
+
     // Loudness metering
     loud_meter: VUMeter,
+
+    // RMS level metering
+    rms_meter: RmsMeter,
+
+    // Session-wide RMS level metering
+    integrated_rms_meter: IntegratedRmsMeter,
+
+    // Zero-crossing rate metering
+    zcr_meter: ZeroCrossingMeter,
 }
 
-// ...so we must Arc it before implementing handler traits on it and sending it
-// to JACK. Furthermore, current coherence rules force us to newtype the Arc
-// before we can implement the foreign XyzHandler traits on it.
-#[derive(Clone)]
-struct JackHandler(Arc<JackState>);
+// ...so we must Arc it before sending it to JACK. The handlers that we send
+// carry it, and the process handler also carries some state which is private
+// to the audio thread, including our input port.
+struct JackHandler {
+    // State shared with the rest of the world
+    state: Arc<JackState>,
+}
+
+struct AudioHandler {
+    // State shared with the rest of the world
+    state: Arc<JackState>,
+
+    // Access to the audio input port
+    input_port: Port<AudioIn>,
+
+    // Audio processing which does not need to talk to JACK
+    processor: AudioProcessor,
+}
+
+// Audio thread's private state, apart from the input port
+//
+// This is kept separate from the JACK handlers so that the audio processing
+// path can be exercised without a JACK server, by feeding it audio buffers
+// and JACK clock timestamps directly.
+//
+struct AudioProcessor {
+    // Meter updates which were accumulated, but not published yet
+    batch: MeterBatch,
+
+    // Generator of test signals, used when the test signal is enabled
+    generator: SignalGenerator,
+
+    // Buffer that the test signal is rendered into, allocated at activation
+    // time so that the audio thread does not need to allocate memory
+    scratch: Box<[Sample]>,
+}
+
+// Audio thread's local meter accumulators
+//
+// Updating the shared meters on every JACK cycle can cause significant atomic
+// traffic when the buffers are small. So we allow the audio thread to
+// accumulate meter updates locally, and only publish them every few cycles.
+// This trades a little bit of metering latency for less contention.
+//
+struct MeterBatch {
+    // Number of JACK cycles to accumulate before publishing meter updates
+    publish_period: u32,
+
+    // Number of JACK cycles that were accumulated since the last publication
+    cycles: u32,
+
+    // Truth that the local accumulators hold unpublished meter updates
+    pending: bool,
+
+    // Local peak meter accumulator
+    peak: Sample,
+
+    // Local quasi-peak meter accumulator
+    quasi_peak: QuasiPeakState,
+
+    // Local VU-meter accumulator
+    vu: VUState,
+
+    // Local RMS meter accumulator
+    rms: RmsState,
+
+    // Local integrated RMS meter accumulator
+    integrated_rms: IntegratedRmsBatch,
+
+    // Local zero-crossing rate meter accumulator
+    zcr: ZeroCrossingState,
+}
+
+// Statistics about the time spent in the process() callback, in microseconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProcessTime {
+    // Moving average of recent callback durations
+    pub average: f32,
+
+    // Maximal callback duration since the last query
+    pub max: f32,
+}
+
+// Configuration of the JACK server, as reported by JackInterface::server_info()
+//
+// The name of the audio driver/device that the server uses is not included,
+// as the JACK client API does not expose it. Check the server's configuration
+// (e.g. with jack_control or QjackCtl) to find it out.
+//
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServerInfo {
+    // Sampling rate, in Hz
+    pub sampling_rate: u32,
+
+    // Number of frames per JACK period
+    pub buffer_size: u32,
+
+    // Truth that the server runs with realtime scheduling
+    pub realtime: bool,
+
+    // Current DSP load of the server, in percent
+    pub cpu_load: f32,
+}
 
 // After activating the Jack client, we present this interface to it
 pub struct JackInterface {
-    // Access to the JACK event handler
-    handler: JackHandler,
+    // Access to the state shared with JACK threads
+    state: Arc<JackState>,
+
+    // Truth that JACK is running with realtime scheduling
+    realtime: bool,
+
+    // Truth that the JACK server was started when we opened our client
+    started_server: bool,
+
+    // Full JACK name of our audio input port
+    input_port_name: String,
 
     // RAII guard for the active JACK client
-    _async_client: AsyncClient<JackHandler, JackHandler>,
+    async_client: AsyncClient<JackHandler, AudioHandler>,
 }
 
 
@@ -74,32 +302,48 @@ pub struct JackInterface {
 //
 impl JackInterface {
     // Set up JACK-based audio processing
-    pub fn new() -> Self {
-        // Create a JACK client
-        let (client, mut status) =
-            Client::new(CLIENT_NAME, ClientOptions::empty())
-                   .expect("Failed to open a JACK client");
+    //
+    // Meter readings will be published every "publish_period" JACK cycles. Use
+    // 1 for minimal latency, more if you have tiny buffers and many meters.
+    //
+    // Fails if the publication period is zero, or if the JACK client cannot be
+    // opened, set up or activated.
+    //
+    // If the JACK server is not running, JACK will try to start it.
+    //
+    pub fn new(publish_period: u32) -> Result<Self> {
+        Self::with_options(publish_period, ClientOptions::empty())
+    }
+
+    // Like new(), but fail with Error::ServerNotRunning instead of starting the
+    // JACK server if it is not running (e.g. for CI and scripted use)
+    pub fn new_no_start_server(publish_period: u32) -> Result<Self> {
+        Self::with_options(publish_period, ClientOptions::NO_START_SERVER)
+    }
+
+    // Set up JACK-based audio processing with some JACK client options
+    fn with_options(publish_period: u32,
+                    options: ClientOptions) -> Result<Self> {
+        if publish_period == 0 {
+            return Err(Error::InvalidConfig("Meter publication period must \
+                                             be nonzero"));
+        }
+
+        // Create a JACK client, telling apart the case where we were not
+        // allowed to start a JACK server and none was running
+        let (client, status) =
+            Client::new(CLIENT_NAME, options).map_err(|e| match e {
+                ::jack::Error::ClientError(status)
+                    if options.contains(ClientOptions::NO_START_SERVER)
+                       && status.contains(ClientStatus::SERVER_FAILED) =>
+                {
+                    Error::ServerNotRunning
+                }
+                other => other.into(),
+            })?;
 
         // Cross-check initial client status
-        let bad_status_mask = ClientStatus::FAILURE
-                              | ClientStatus::INVALID_OPTION
-                              | ClientStatus::SERVER_FAILED
-                              | ClientStatus::SERVER_ERROR
-                              | ClientStatus::NO_SUCH_CLIENT
-                              | ClientStatus::LOAD_FAILURE
-                              | ClientStatus::INIT_FAILURE
-                              | ClientStatus::SHM_FAILURE
-                              | ClientStatus::VERSION_ERROR
-                              | ClientStatus::BACKEND_ERROR
-                              | ClientStatus::CLIENT_ZOMBIE;
-        assert_eq!(status & bad_status_mask, ClientStatus::empty(),
-                   "Bad client initialization status");
-        status.remove(bad_status_mask);
-        let ignored_status_mask = ClientStatus::NAME_NOT_UNIQUE
-                                  | ClientStatus::SERVER_STARTED;
-        status.remove(ignored_status_mask);
-        assert_eq!(status, ClientStatus::empty(),
-                   "Unknown client initialization status");
+        let started_server = check_client_status(status)?;
 
         // Say hi to the user
         // FIXME: No printing in library modules...
@@ -108,36 +352,150 @@ impl JackInterface {
         print!("buffer size is {}, ", client.buffer_size());
         println!("initial frame time is {} µs.", ::jack::get_time());
 
+        // Check if JACK is running in realtime mode
+        //
+        // SAFETY: The client pointer is valid as long as the client is alive
+        //
+        let realtime =
+            unsafe { ::jack_sys::jack_is_realtime(client.raw()) != 0 };
+
         // Register an audio input
-        let input_port =
-            client.register_port(PORT_NAME, AudioIn)
-                  .expect("Failed to register input port");
+        let input_port = client.register_port(PORT_NAME, AudioIn)?;
+        let input_port_name = format!("{}:{}", client.name(), PORT_NAME);
 
         // Setup shared state between JACK threads and rest of the application
-        let handler = JackHandler(Arc::new(JackState {
-            alive: AtomicBool::new(true),
+        let sampling_rate = client.sample_rate() as u32;
+        let state = Arc::new(JackState::new(sampling_rate, ::jack::get_time()));
+        let notification_handler = JackHandler { state: state.clone() };
+        let process_handler = AudioHandler {
+            state: state.clone(),
             input_port,
-            next_time: AtomicU64::new(::jack::get_time()),
-            peak_meter: SamplePeakMeter::new(),
-            loud_meter: VUMeter::new(client.sample_rate() as u32),
-        }));
+            processor: AudioProcessor::new(publish_period,
+                                           sampling_rate,
+                                           client.buffer_size() as usize),
+        };
 
         // Start JACK
-        let _async_client = client.activate_async(
-            handler.clone(),
-            handler.clone(),
-        ).expect("Failed to activate client");
+        let async_client =
+            client.activate_async(notification_handler, process_handler)?;
 
-        // Return interface / RAII struct
-        Self {
-            handler,
-            _async_client,
+        // JACK may have changed the sampling rate between client creation and
+        // activation, in which case it notified the sample_rate callback. Make
+        // sure that the meters did follow.
+        // NOTE: Meters are unaffected by buffer size, so there's nothing to
+        //       check there.
+        let sampling_rate = async_client.as_client().sample_rate() as u32;
+        if state.sampling_rate.load(Ordering::Acquire) != sampling_rate {
+            return Err(Error::InvalidConfig("Meters did not follow a change \
+                                             of JACK's sampling rate"));
         }
+        state.check_sampling_rate(sampling_rate);
+
+        // Return interface / RAII struct
+        Ok(Self {
+            state,
+            realtime,
+            started_server,
+            input_port_name,
+            async_client,
+        })
     }
 
     // Check if the audio thread is still alive. Please do this periodically
     pub fn is_alive(&self) -> bool {
-        self.handler.is_alive()
+        self.state.is_alive()
+    }
+
+    // Check if JACK is running with realtime scheduling
+    //
+    // This reflects the JACK server's configuration. JACK does not tell us if
+    // the OS actually granted RT priority to our process thread, so this can
+    // still be true if e.g. rtprio limits are too low. Check the JACK server's
+    // logs to find out about that.
+    //
+    pub fn is_realtime(&self) -> bool {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.realtime
+    }
+
+    // Check if opening our client started the JACK server
+    //
+    // If so, we are the JACK server's first client, and applications may want
+    // to offer stopping it on exit. Note that the server may still have gained
+    // other clients since then.
+    //
+    pub fn started_server(&self) -> bool {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.started_server
+    }
+
+    // Current sampling rate of the JACK server, in Hz
+    pub fn sampling_rate(&self) -> u32 {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.async_client.as_client().sample_rate() as u32
+    }
+
+    // Query the current configuration of the JACK server, for informational
+    // purposes (e.g. to tell which of several JACK setups we are talking to)
+    pub fn server_info(&self) -> ServerInfo {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        let client = self.async_client.as_client();
+        ServerInfo {
+            sampling_rate: client.sample_rate() as u32,
+            buffer_size: client.buffer_size(),
+            realtime: self.realtime,
+            cpu_load: client.cpu_load(),
+        }
+    }
+
+    // Convert a number of audio frames to seconds, at the current sampling
+    // rate. This measures audio time, which may not match wall clock time, for
+    // example when JACK is freewheeling (see units::frames_to_secs()).
+    pub fn frames_to_secs(&self, frames: u64) -> f64 {
+        units::frames_to_secs(frames, self.sampling_rate())
+    }
+
+    // Convert a duration of the JACK clock (as used by next_time() and
+    // time_since_update_us()) from µs to seconds
+    pub fn usecs_to_secs(&self, usecs: Time) -> f64 {
+        units::usecs_to_secs(usecs)
+    }
+
+    // Full JACK name of our audio input, for use with connect()/disconnect()
+    pub fn input_port_name(&self) -> &str {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        &self.input_port_name
+    }
+
+    // List the full names of the JACK audio ports which match some flags
+    //
+    // For example, use PortFlags::IS_OUTPUT to list the ports that can be
+    // connected to our input. Like everything that follows, this should be
+    // called from a non-realtime thread.
+    //
+    pub fn available_ports(&self, flags: PortFlags) -> Vec<String> {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.async_client.as_client().ports(None, Some(AUDIO_TYPE), flags)
+    }
+
+    // Connect two JACK ports, given their full names
+    pub fn connect(&self,
+                   source: &str,
+                   destination: &str) -> Result<()> {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.async_client.as_client()
+                         .connect_ports_by_name(source, destination)?;
+        Ok(())
+    }
+
+    // Disconnect two JACK ports, given their full names
+    pub fn disconnect(&self,
+                      source: &str,
+                      destination: &str) -> Result<()> {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.async_client.as_client()
+                         .disconnect_ports_by_name(source, destination)?;
+        Ok(())
     }
 
     // Query JACK clock as of the end of the last processed audio frame
@@ -147,32 +505,454 @@ impl JackInterface {
     //
     pub fn next_time(&self) -> Time {
         debug_assert!(self.is_alive(), "Audio thread has died.");
-        self.handler.next_time()
+        self.state.next_time()
+    }
+
+    // Query how much time has elapsed since the meters were last updated, in µs
+    //
+    // If the audio thread stalls, the meters keep their last values. This lets
+    // you detect that and mark their readings as stale. In normal operation,
+    // expect values up to a few JACK periods (times the meter publication
+    // period). Paused meters are not considered stale.
+    //
+    pub fn time_since_update_us(&self) -> Time {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.time_since_update(::jack::get_time())
+    }
+
+    // Check if audio data was dropped shortly before the last processed frame
+    //
+    // After an xrun, the audio stream has a discontinuity, and the meters will
+    // take some time to recover from it. Readings taken during that time can
+    // be marked or discarded.
+    //
+    pub fn had_recent_xrun(&self) -> bool {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.had_recent_xrun()
+    }
+
+    // Query how many times audio data was dropped since startup
+    pub fn xrun_count(&self) -> u64 {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.xruns.load(Ordering::Relaxed)
     }
 
     // Query the peak meter for its current dBFS value and reset it
+    //
+    // While metering is paused, the peak meter is not reset, so that it keeps
+    // displaying the same value.
+    //
     pub fn read_and_reset_peak(&self) -> Decibel {
+        self.read_and_reset_peak_with_hold().0
+    }
+
+    // Like read_and_reset_peak(), but also query the held peak (highest peak
+    // since startup or reset_peak_hold()) in dBFS in a consistent fashion
+    //
+    // The held peak is that of read_max_peak_with_time(), but it is never
+    // lower than the current peak, even right after reset_peak_hold().
+    //
+    pub fn read_and_reset_peak_with_hold(&self) -> (Decibel, Decibel) {
         debug_assert!(self.is_alive(), "Audio thread has died.");
-        self.handler.0.peak_meter.read_and_reset()
+        self.state.read_and_reset_peak_with_hold()
     }
 
-    // Query the VU-meter for its current VUFS value
+    // Query the quasi-peak meter for its current dBFS value and reset it
+    //
+    // Unlike the peak meter, this needs levels to be sustained for about a
+    // millisecond to fully register, so it is less twitchy on displays. As
+    // with read_and_reset_peak(), nothing is reset while metering is paused.
+    //
+    pub fn read_and_reset_quasi_peak(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.read_and_reset_quasi_peak()
+    }
+
+    // Query how many audio frames were fed into the meters since startup
+    //
+    // Frames received while metering is paused are not counted, so this can be
+    // used to compute the measurement duration independently of the JACK clock.
+    //
+    pub fn frames_processed(&self) -> u64 {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.frames_processed()
+    }
+
+    // Check if enough audio was fed into the meters since startup for all of
+    // them to give meaningful readings
+    //
+    // Until then, meters which average the signal over some time window read
+    // artificially low, as that window is still filling up. Logging and
+    // alarms should thus be suppressed until the meters are warmed up (see
+    // LevelAlarm::set_armed()). Like frames_processed(), this ignores the
+    // time spent paused.
+    //
+    pub fn warmed_up(&self) -> bool {
+        self.frames_to_secs(self.frames_processed()) >= WARMUP_TIME
+    }
+
+    // Reset the held peak, e.g. at the start of a new session
+    pub fn reset_peak_hold(&self) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.max_peak.store(NO_MAX_PEAK, Ordering::Relaxed);
+    }
+
+    // Query the highest sample peak since startup or reset_peak_hold(), in
+    // dBFS, along with the JACK clock timestamp at which it occurred
+    //
+    // This is meant for tracking down rare glitches in long sessions: the
+    // timestamp is that of the peak sample itself, interpolated within its
+    // JACK period, and uses the same clock as next_time(). The peak is
+    // recorded with reduced precision, and may read up to 0.001 dB higher than
+    // the actual peak sample, but never lower. If no peak was recorded yet,
+    // this returns -inf dBFS at time 0.
+    //
+    pub fn read_max_peak_with_time(&self) -> (Decibel, Time) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.max_peak_with_time()
+    }
+
+    // Query the sample peak of the last processed audio buffer, in dBFS
+    //
+    // Unlike the peak meter, this is updated on every JACK cycle and never
+    // held, so it is only suitable for very low latency visual feedback.
+    //
+    pub fn last_buffer_peak(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.last_buffer_peak()
+    }
+
+    // Query the VU-meter for its current value, in VU relative to the level
+    // set by set_vu_reference() (VUFS by default)
     pub fn read_loudness(&self) -> Decibel {
         debug_assert!(self.is_alive(), "Audio thread has died.");
-        self.handler.0.loud_meter.read()
+        self.state.loud_meter.read()
+    }
+
+    // Query the VU-meter's peak hold overlay, in the same unit as
+    // read_loudness()
+    pub fn read_loudness_peak_hold(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.loud_meter.read_peak_hold()
+    }
+
+    // Query how many times the VU-meter had to clamp out-of-range samples
+    pub fn vu_sanitized_events(&self) -> u64 {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.loud_meter.sanitized_events()
+    }
+
+    // Set the level which the VU-meter should read as 0 VU, in dBFS
+    pub fn set_vu_reference(&self, reference: Decibel) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.loud_meter.set_reference(reference);
+    }
+
+    // Query the RMS meter for its current dBFS value ("Fast" time weighting)
+    pub fn read_rms(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.rms_meter.read()
+    }
+
+    // Query the session-wide RMS level in dBFS, ignoring silent passages
+    //
+    // This is a cheap approximation of integrated loudness, but it is NOT an
+    // ITU-R BS.1770 LUFS measurement. See IntegratedRmsMeter for details.
+    //
+    pub fn read_integrated_rms(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.integrated_rms_meter.read()
+    }
+
+    // Use the current session-wide RMS level as a dialogue reference, see
+    // IntegratedRmsMeter::capture_reference(). Returns the captured level in
+    // dBFS, or None if nothing was measured yet.
+    pub fn capture_dialogue_reference(&self) -> Option<Decibel> {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.integrated_rms_meter.capture_reference()
+    }
+
+    // Query the session-wide RMS level in dB relative to the level captured
+    // by capture_dialogue_reference() (or dBFS if none was captured)
+    pub fn read_integrated_rms_vs_dialogue(&self) -> Decibel {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.integrated_rms_meter.read_relative()
+    }
+
+    // Query the current zero-crossing rate of the signal, in crossings per
+    // second. This is a cheap proxy for how noisy the signal is.
+    pub fn zero_crossing_rate(&self) -> f32 {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.zcr_meter.rate_hz()
+    }
+
+    // Start a new session for the integrated RMS level
+    //
+    // As with reset_peak_hold(), this waits for the audio thread to carry out
+    // the reset, so audio that was processed beforehand is not accounted for.
+    //
+    pub fn reset_integrated_rms(&self) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.request_resets(RESET_INTEGRATED_RMS);
+        self.state.wait_for_resets(RESET_INTEGRATED_RMS);
+    }
+
+    // Query how much time the audio thread spends processing each JACK cycle
+    //
+    // Compare this to the JACK cycle duration to know how close we are to
+    // missing the realtime deadline. The maximum is reset on every query.
+    //
+    pub fn process_time_us(&self) -> ProcessTime {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.query_process_time()
+    }
+
+    // Stop feeding audio into the meters, which will keep their last values.
+    // Audio processing and the JACK clock keep running in the meantime.
+    pub fn pause_metering(&self) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.paused.store(true, Ordering::Relaxed);
+    }
+
+    // Resume feeding audio into the meters after pause_metering()
+    pub fn resume_metering(&self) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.paused.store(false, Ordering::Relaxed);
+    }
+
+    // Check if metering is currently paused
+    pub fn is_metering_paused(&self) -> bool {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.paused.load(Ordering::Relaxed)
+    }
+
+    // Check if JACK is currently freewheeling
+    //
+    // The JACK clock then runs much faster than real time, so time-based
+    // display ballistics (see DecayingPeak::update()) should not advance.
+    //
+    pub fn is_freewheeling(&self) -> bool {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.freewheeling.load(Ordering::Relaxed)
+    }
+
+    // Feed a test signal with a certain RMS level (in dBFS) into the meters,
+    // instead of the audio input, until disable_test_signal() is called
+    //
+    // Unlike the calibration self-test, this goes through the actual audio
+    // processing path, and can thus validate a live setup.
+    //
+    pub fn enable_test_signal(&self, signal: TestSignal, rms_level: Decibel) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.test_signal.store(encode_test_signal(signal, rms_level),
+                                     Ordering::Relaxed);
+    }
+
+    // Go back to feeding the audio input into the meters
+    pub fn disable_test_signal(&self) {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.test_signal.store(NO_TEST_SIGNAL, Ordering::Relaxed);
+    }
+
+    // Start a measurement session, see MeasurementSession
+    //
+    // The meters are reset by the audio thread, so that audio which it
+    // processed before the session started is not accounted for, and this
+    // waits for it to be done (which takes up to one JACK period).
+    //
+    pub fn start_session(&mut self) -> MeasurementSession<'_> {
+        debug_assert!(self.is_alive(), "Audio thread has died.");
+        self.state.request_resets(RESET_SESSION);
+        self.state.wait_for_resets(RESET_SESSION);
+        MeasurementSession {
+            start_xruns: self.xrun_count(),
+            interface: self,
+        }
     }
 }
 
+
+// A measurement session, which measures the input over some period of time
+//
+// Starting a session resets the meters which accumulate data over time (peak
+// hold and integrated RMS level), and finishing it produces a report of what
+// was measured in the meantime. Dropping a session without finishing it just
+// discards the measurement.
+//
+// Since sessions reset shared meters, there can only be one at a time, and
+// the JACK interface can only be accessed through the session while it runs.
+//
+pub struct MeasurementSession<'a> {
+    // Interface to the JACK audio machinery
+    interface: &'a mut JackInterface,
+
+    // Number of xruns that occurred before the session started
+    start_xruns: u64,
+}
+
+impl MeasurementSession<'_> {
+    // Access the JACK interface, e.g. to display meter readings while the
+    // session is running
+    pub fn interface(&self) -> &JackInterface {
+        self.interface
+    }
+
+    // Finish the measurement session and report what was measured
+    pub fn finish(self) -> MeasurementReport {
+        let interface = self.interface;
+        debug_assert!(interface.is_alive(), "Audio thread has died.");
+        interface.state.session_report(interface.sampling_rate(),
+                                       self.start_xruns)
+    }
+}
+
+// Summary of a measurement session
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeasurementReport {
+    // Duration of the audio that was measured (excluding pauses), in seconds
+    pub duration_secs: f64,
+
+    // Highest sample peak, in dBFS
+    pub max_peak: Decibel,
+
+    // Session-wide RMS level, ignoring silent passages, in dBFS
+    pub integrated_rms: Decibel,
+
+    // Number of times audio data was dropped during the session
+    pub xruns: u64,
+}
+
+// Cross-check the status of a newly opened JACK client, and tell whether
+// opening it started the JACK server
+fn check_client_status(mut status: ClientStatus) -> Result<bool> {
+    let bad_status_mask = ClientStatus::FAILURE
+                          | ClientStatus::INVALID_OPTION
+                          | ClientStatus::SERVER_FAILED
+                          | ClientStatus::SERVER_ERROR
+                          | ClientStatus::NO_SUCH_CLIENT
+                          | ClientStatus::LOAD_FAILURE
+                          | ClientStatus::INIT_FAILURE
+                          | ClientStatus::SHM_FAILURE
+                          | ClientStatus::VERSION_ERROR
+                          | ClientStatus::BACKEND_ERROR
+                          | ClientStatus::CLIENT_ZOMBIE;
+    if status.intersects(bad_status_mask) {
+        let bad_status = status & bad_status_mask;
+        return Err(::jack::Error::ClientError(bad_status).into());
+    }
+    let started_server = status.contains(ClientStatus::SERVER_STARTED);
+    let ignored_status_mask = ClientStatus::NAME_NOT_UNIQUE
+                              | ClientStatus::SERVER_STARTED;
+    status.remove(ignored_status_mask);
+    if status != ClientStatus::empty() {
+        // Newer JACK versions may report statuses that we don't know of
+        return Err(::jack::Error::ClientError(status).into());
+    }
+    Ok(started_server)
+}
+
+// Pack a test signal configuration into a JackState::test_signal value
+fn encode_test_signal(signal: TestSignal, rms_level: Decibel) -> u64 {
+    let kind: u64 = match signal {
+        TestSignal::Tone => 1,
+        TestSignal::PinkNoise => 2,
+    };
+    (kind << 32) | (rms_level.to_bits() as u64)
+}
+
+// Unpack a JackState::test_signal value
+fn decode_test_signal(bits: u64) -> Option<(TestSignal, Decibel)> {
+    let signal = match bits >> 32 {
+        0 => return None,
+        1 => TestSignal::Tone,
+        2 => TestSignal::PinkNoise,
+        _ => unreachable!("Invalid test signal kind"),
+    };
+    Some((signal, Decibel::from_bits(bits as u32)))
+}
+
+// Number of low-order bits of a positive peak sample which do not fit in a
+// JackState::max_peak value (the sign bit being always zero)
+const MAX_PEAK_DROPPED_BITS: u32 = 31 - (64 - MAX_PEAK_TIME_BITS);
+
+// Pack a positive peak sample and its timestamp, relative to some base time,
+// into a JackState::max_peak value
+//
+// The low-order bits of the sample are dropped, rounding up so that the
+// recorded peak is never lower than the actual one. Since positive floats are
+// ordered like their bit patterns, recorded peaks can be compared by looking
+// at the high-order bits of max_peak values. Timestamps saturate.
+//
+fn encode_max_peak(peak: Sample, time_offset: Time) -> u64 {
+    let rounding = (1 << MAX_PEAK_DROPPED_BITS) - 1;
+    let peak_bits =
+        (peak.to_bits() as u64 + rounding) >> MAX_PEAK_DROPPED_BITS;
+    (peak_bits << MAX_PEAK_TIME_BITS) | time_offset.min(MAX_PEAK_TIME_MASK)
+}
+
+// Unpack a JackState::max_peak value into a dBFS peak and its timestamp,
+// relative to the base time that was used when packing it
+fn decode_max_peak(bits: u64) -> (Decibel, Time) {
+    let peak_bits = (bits >> MAX_PEAK_TIME_BITS) as u32;
+    let peak = Sample::from_bits(peak_bits << MAX_PEAK_DROPPED_BITS);
+    (20.0 * peak.log10(), bits & MAX_PEAK_TIME_MASK)
+}
+
+// Compute the JackState::max_peak value which results from recording a new
+// peak, if it is higher than the current one
+fn update_max_peak(max_peak: u64, new_max_peak: u64) -> Option<u64> {
+    if new_max_peak >> MAX_PEAK_TIME_BITS > max_peak >> MAX_PEAK_TIME_BITS {
+        Some(new_max_peak)
+    } else {
+        None
+    }
+}
+
+// Estimate the JACK clock timestamp of the sample at index "idx" of an audio
+// buffer of "len" samples, which spans timestamps from "start" to "end"
+fn interpolate_time(start: Time, end: Time, idx: usize, len: usize) -> Time {
+    start + end.saturating_sub(start) * (idx as u64) / (len.max(1) as u64)
+}
+
 // Internal interface of the JACK audio machinery
-impl JackHandler {
+impl JackState {
+    // Set up the shared state for a given sampling rate, starting at a given
+    // JACK clock timestamp
+    fn new(sampling_rate: u32, time: Time) -> Self {
+        Self {
+            alive: AtomicBool::new(true),
+            paused: AtomicBool::new(false),
+            freewheeling: AtomicBool::new(false),
+            sampling_rate: AtomicU32::new(sampling_rate),
+            start_time: time,
+            next_time: AtomicU64::new(time),
+            frames_processed: AtomicU64::new(0),
+            session_start_frames: AtomicU64::new(0),
+            last_xrun: AtomicU64::new(NO_XRUN),
+            xruns: AtomicU64::new(0),
+            test_signal: AtomicU64::new(NO_TEST_SIGNAL),
+            last_buffer_peak: AtomicU32::new(0.0f32.to_bits()),
+            max_peak: AtomicU64::new(NO_MAX_PEAK),
+            process_time_avg: AtomicU32::new(0.0f32.to_bits()),
+            process_time_max: AtomicU32::new(0.0f32.to_bits()),
+            pending_resets: AtomicU32::new(0),
+            peak_meter: SamplePeakMeter::new_for(sampling_rate),
+            quasi_peak_meter: QuasiPeakMeter::new_for(sampling_rate),
+            loud_meter: VUMeter::new_for(sampling_rate),
+            rms_meter: RmsMeter::new_for(sampling_rate),
+            integrated_rms_meter: IntegratedRmsMeter::new_for(sampling_rate),
+            zcr_meter: ZeroCrossingMeter::new_for(sampling_rate),
+        }
+    }
+
     // Check if the audio thread is still alive, please do this periodically
     fn is_alive(&self) -> bool {
-        self.0.alive.load(Ordering::Relaxed)
+        self.alive.load(Ordering::Relaxed)
     }
 
     // Mark the audio thread as dead
     fn mark_dead(&self) {
-        self.0.alive.store(false, Ordering::Relaxed);
+        self.alive.store(false, Ordering::Relaxed);
     }
 
     // Query JACK clock as of the end of the last processed audio frame
@@ -181,7 +961,7 @@ impl JackHandler {
     // made during the process() callback. Should be called first by clients.
     //
     fn next_time(&self) -> Time {
-        self.0.next_time.load(Ordering::Acquire)
+        self.next_time.load(Ordering::Acquire)
     }
 
     // Update the JACK clock to account for newly processed frames
@@ -189,19 +969,215 @@ impl JackHandler {
     // Provides a Release barrier so that clients can synchronize with writes
     // made during the process() callback. Should be called last by process().
     //
-    fn update_time(&self, scope: &ProcessScope) {
-        let next_time =
-            scope.cycle_times()
-                 .expect("JACK lib does not seem to support cycle timing")
-                 .next_usecs;
-        self.0.next_time.store(next_time, Ordering::Release);
+    fn update_time(&self, next_time: Time) {
+        self.next_time.store(next_time, Ordering::Release);
+    }
+
+    // Query how many audio frames were fed into the meters since startup
+    fn frames_processed(&self) -> u64 {
+        self.frames_processed.load(Ordering::Relaxed)
+    }
+
+    // Query how much time has elapsed between the last meter update and a
+    // given JACK clock timestamp, in µs
+    fn time_since_update(&self, now: Time) -> Time {
+        now.saturating_sub(self.next_time())
+    }
+
+    // Report what was measured since the last measurement session started,
+    // given the sampling rate and the number of xruns at that point
+    fn session_report(&self,
+                      sampling_rate: u32,
+                      start_xruns: u64) -> MeasurementReport {
+        // Synchronize with the last meter publication of the audio thread
+        self.next_time();
+        let frames = self.frames_processed()
+                     - self.session_start_frames.load(Ordering::Relaxed);
+        MeasurementReport {
+            duration_secs: units::frames_to_secs(frames, sampling_rate),
+            max_peak: self.max_peak_with_time().0,
+            integrated_rms: self.integrated_rms_meter.read(),
+            xruns: self.xruns.load(Ordering::Relaxed) - start_xruns,
+        }
+    }
+
+    // Record that audio data was dropped at a given JACK clock timestamp
+    fn record_xrun(&self, time: Time) {
+        self.last_xrun.store(time, Ordering::Relaxed);
+        self.xruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Check if audio data was dropped less than XRUN_RECOVERY_TIME before the
+    // end of the last processed frame
+    fn had_recent_xrun(&self) -> bool {
+        let last_xrun = self.last_xrun.load(Ordering::Relaxed);
+        last_xrun != NO_XRUN
+            && self.next_time().saturating_sub(last_xrun) < XRUN_RECOVERY_TIME
+    }
+
+    // Query the highest sample peak since startup or the last peak hold
+    // reset, in dBFS, along with the JACK clock timestamp at which it occurred
+    // (or -inf dBFS at time 0 if no peak was recorded yet)
+    fn max_peak_with_time(&self) -> (Decibel, Time) {
+        let max_peak = self.max_peak.load(Ordering::Relaxed);
+        if max_peak == NO_MAX_PEAK {
+            return (Decibel::NEG_INFINITY, 0);
+        }
+        let (peak, time_offset) = decode_max_peak(max_peak);
+        (peak, self.start_time + time_offset)
+    }
+
+    // Query the peak meter and the held peak, resetting the former unless
+    // metering is paused (see JackInterface::read_and_reset_peak_with_hold())
+    fn read_and_reset_peak_with_hold(&self) -> (Decibel, Decibel) {
+        let (peak, _) = if self.paused.load(Ordering::Relaxed) {
+            self.peak_meter.read_with_hold()
+        } else {
+            self.peak_meter.read_and_reset_with_hold()
+        };
+        let (max_peak, _) = self.max_peak_with_time();
+        (peak, max_peak.max(peak))
+    }
+
+    // Query the quasi-peak meter, resetting it unless metering is paused
+    fn read_and_reset_quasi_peak(&self) -> Decibel {
+        if self.paused.load(Ordering::Relaxed) {
+            self.quasi_peak_meter.read()
+        } else {
+            self.quasi_peak_meter.read_and_reset()
+        }
+    }
+
+    // Query the sample peak of the last processed audio buffer, in dBFS
+    fn last_buffer_peak(&self) -> Decibel {
+        let peak_bits = self.last_buffer_peak.load(Ordering::Relaxed);
+        20.0 * Sample::from_bits(peak_bits).log10()
+    }
+
+    // Record the duration of a process() callback
+    //
+    // This should only be called by the audio thread, as the moving average
+    // update is not atomic.
+    //
+    fn record_process_time(&self, duration: Duration) {
+        // Weight of the newest duration in the moving average
+        const AVERAGE_WEIGHT: f32 = 0.01;
+        let duration_us = duration.as_secs_f32() * 1_000_000.0;
+        let old_avg =
+            f32::from_bits(self.process_time_avg.load(Ordering::Relaxed));
+        let new_avg = old_avg + (duration_us - old_avg) * AVERAGE_WEIGHT;
+        self.process_time_avg.store(new_avg.to_bits(), Ordering::Relaxed);
+        // NOTE: Positive floats are ordered like their bit patterns
+        self.process_time_max.fetch_max(duration_us.to_bits(),
+                                        Ordering::Relaxed);
+    }
+
+    // Query statistics about the duration of process() callbacks, resetting
+    // the maximal duration
+    fn query_process_time(&self) -> ProcessTime {
+        let average_bits = self.process_time_avg.load(Ordering::Relaxed);
+        let max_bits = self.process_time_max.swap(0.0f32.to_bits(),
+                                                  Ordering::Relaxed);
+        ProcessTime {
+            average: f32::from_bits(average_bits),
+            max: f32::from_bits(max_bits),
+        }
+    }
+
+    // Ask the audio thread to carry out some meter resets (see RESET_XYZ)
+    fn request_resets(&self, resets: u32) {
+        self.pending_resets.fetch_or(resets, Ordering::Relaxed);
+    }
+
+    // Wait for the audio thread to carry out some requested meter resets, or
+    // to die trying
+    //
+    // Provides an Acquire barrier so that the caller can see the reset meters.
+    //
+    fn wait_for_resets(&self, resets: u32) {
+        while self.is_alive()
+              && self.pending_resets.load(Ordering::Acquire) & resets != 0
+        {
+            std::thread::sleep(RESET_POLL_PERIOD);
+        }
+    }
+
+    // Feed audio samples, which span JACK clock timestamps from
+    // "current_time" to "next_time", into the meters
+    fn feed_meters(&self,
+                   batch: &mut MeterBatch,
+                   samples: &[Sample],
+                   current_time: Time,
+                   next_time: Time) {
+        // Publish the peak of this buffer for low-latency feedback
+        let (peak_idx, buffer_peak) =
+            samples.iter()
+                   .map(|x| x.abs())
+                   .enumerate()
+                   .fold((0, 0.0f32), |(i, x), (j, y)| {
+                       if y > x { (j, y) } else { (i, x) }
+                   });
+        self.last_buffer_peak.store(buffer_peak.to_bits(), Ordering::Relaxed);
+
+        // Record the peak and its timestamp if it is the highest so far
+        let len = samples.len();
+        let peak_time = interpolate_time(current_time,
+                                         next_time,
+                                         peak_idx,
+                                         len);
+        // NOTE: Infinite peaks are ignored, like the peak meter does
+        if buffer_peak.is_finite() {
+            let new_max_peak =
+                encode_max_peak(buffer_peak,
+                                peak_time.saturating_sub(self.start_time));
+            // NOTE: This can fail, but only if there is no new max peak
+            let _ = self.max_peak.fetch_update(
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+                |max_peak| update_max_peak(max_peak, new_max_peak)
+            );
+        }
+
+        // Update local meters with the new audio samples
+        batch.integrate(self, samples);
+        self.frames_processed.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    // Check, in debug builds, that the meters are configured for the sampling
+    // rate that JACK currently reports
+    //
+    // JACK may report a new sampling rate before the sample_rate() callback is
+    // done reconfiguring the meters, so they are only checked once it is.
+    //
+    fn check_sampling_rate(&self, sampling_rate: u32) {
+        if self.sampling_rate.load(Ordering::Acquire) != sampling_rate {
+            return;
+        }
+        debug_assert_eq!(sampling_rate,
+                         self.quasi_peak_meter.sampling_rate(),
+                         "Quasi-peak meter is set up for the wrong sampling \
+                          rate");
+        debug_assert_eq!(sampling_rate,
+                         self.loud_meter.sampling_rate(),
+                         "VU-meter is set up for the wrong sampling rate");
+        debug_assert_eq!(sampling_rate,
+                         self.rms_meter.sampling_rate(),
+                         "RMS meter is set up for the wrong sampling rate");
+        debug_assert_eq!(sampling_rate,
+                         self.integrated_rms_meter.sampling_rate(),
+                         "Integrated RMS meter is set up for the wrong \
+                          sampling rate");
+        debug_assert_eq!(sampling_rate,
+                         self.zcr_meter.sampling_rate(),
+                         "Zero-crossing meter is set up for the wrong \
+                          sampling rate");
     }
 
     // JACK callback wrapper that makes sure the audio thread honors its own
     // liveness signal, prevents panic-induced UB, and translates panics or
     // voluntary exits into implicit setting of the death signal.
     fn callback_guard<F>(&self, callback: F) -> Control
-        where F: FnMut() -> Control + panic::UnwindSafe
+        where F: FnOnce() -> Control + panic::UnwindSafe
     {
         if !self.is_alive() { return Control::Quit; }
         let result = panic::catch_unwind(callback);
@@ -213,28 +1189,208 @@ impl JackHandler {
     }
 }
 
-impl ProcessHandler for JackHandler {
-    // Hook to process incoming audio data
-    fn process(&mut self, _: &Client, scope: &ProcessScope) -> Control {
-        self.callback_guard(|| {
-            // Fetch input frames
-            let input = self.0.input_port.as_slice(scope);
+// Local accumulation of meter updates on the audio thread
+impl MeterBatch {
+    // Prepare to publish meter updates every "publish_period" JACK cycles
+    fn new(publish_period: u32) -> Self {
+        Self {
+            publish_period,
+            cycles: 0,
+            pending: false,
+            peak: 0.0,
+            quasi_peak: QuasiPeakState::default(),
+            vu: VUState::default(),
+            rms: RmsState::default(),
+            integrated_rms: IntegratedRmsBatch::default(),
+            zcr: ZeroCrossingState::default(),
+        }
+    }
+
+    // Accumulate new audio samples into the local meters
+    fn integrate(&mut self, state: &JackState, input: &[Sample]) {
+        // Without batching, we can just feed the shared meters directly
+        let input = input.iter().copied();
+        if self.publish_period == 1 {
+            state.peak_meter.integrate(input.clone());
+            state.quasi_peak_meter.integrate(input.clone());
+            state.loud_meter.integrate(input.clone());
+            state.rms_meter.integrate(input.clone());
+            state.integrated_rms_meter.integrate(input.clone());
+            state.zcr_meter.integrate(input);
+            return;
+        }
+
+        // Otherwise, accumulate into the local meters
+        if !self.pending {
+            self.peak = state.peak_meter.begin_batch();
+            self.quasi_peak = state.quasi_peak_meter.begin_batch();
+            self.vu = state.loud_meter.begin_batch();
+            self.rms = state.rms_meter.begin_batch();
+            self.integrated_rms = state.integrated_rms_meter.begin_batch();
+            self.zcr = state.zcr_meter.begin_batch();
+            self.pending = true;
+        }
+        state.peak_meter.integrate_batch(&mut self.peak, input.clone());
+        state.quasi_peak_meter.integrate_batch(&mut self.quasi_peak,
+                                               input.clone());
+        state.loud_meter.integrate_batch(&mut self.vu, input.clone());
+        state.rms_meter.integrate_batch(&mut self.rms, input.clone());
+        state.integrated_rms_meter.integrate_batch(&mut self.integrated_rms,
+                                                   input.clone());
+        state.zcr_meter.integrate_batch(&mut self.zcr, input);
+    }
+
+    // Carry out meter resets which were requested by other threads (see
+    // RESET_XYZ), without letting local meter updates overwrite them
+    fn reset(&mut self, state: &JackState, resets: u32) {
+        if resets & RESET_SESSION != 0 {
+            // Local peaks predate the session, so discard them
+            state.peak_meter.reset();
+            self.peak = state.peak_meter.begin_batch();
+            state.max_peak.store(NO_MAX_PEAK, Ordering::Relaxed);
+            let frames = state.frames_processed();
+            state.session_start_frames.store(frames, Ordering::Relaxed);
+        }
+        if resets & (RESET_INTEGRATED_RMS | RESET_SESSION) != 0 {
+            // Local integrated RMS data predates the reset, so discard it
+            state.integrated_rms_meter.reset();
+            self.integrated_rms = state.integrated_rms_meter.begin_batch();
+        }
+    }
 
-            // Update meters with the new audio samples
-            self.0.peak_meter.integrate(input.iter().copied());
-            self.0.loud_meter.integrate(input.iter().copied());
+    // Account for the end of a JACK cycle, then publish accumulated meter
+    // updates if enough JACK cycles have elapsed, and tell whether that was
+    // the case.
+    fn try_publish(&mut self, state: &JackState) -> bool {
+        self.cycles += 1;
+        if self.cycles < self.publish_period { return false; }
+        if self.pending {
+            state.peak_meter.end_batch(self.peak);
+            state.quasi_peak_meter.end_batch(self.quasi_peak);
+            state.loud_meter.end_batch(self.vu);
+            state.rms_meter.end_batch(self.rms);
+            state.integrated_rms_meter.end_batch(self.integrated_rms);
+            state.zcr_meter.end_batch(self.zcr);
+            self.pending = false;
+        }
+        self.cycles = 0;
+        true
+    }
+}
+
+// Audio processing of the audio thread
+impl AudioProcessor {
+    // Prepare to process audio at a given sampling rate and buffer size,
+    // publishing meter updates every "publish_period" JACK cycles
+    fn new(publish_period: u32,
+           sampling_rate: u32,
+           buffer_size: usize) -> Self {
+        Self {
+            batch: MeterBatch::new(publish_period),
+            generator: SignalGenerator::new(TestSignal::Tone,
+                                            -20.0,
+                                            sampling_rate),
+            scratch: vec![0.0; buffer_size.max(1)].into_boxed_slice(),
+        }
+    }
 
-            // Update client view of the JACK clock
-            self.update_time(scope);
+    // Process a JACK period worth of audio input, which spans JACK clock
+    // timestamps from "current_time" to "next_time"
+    fn process(&mut self,
+               state: &JackState,
+               input: &[Sample],
+               current_time: Time,
+               next_time: Time,
+               sampling_rate: u32) {
+        // Carry out meter resets which were requested by other threads, then
+        // let them know about it (even if metering is paused)
+        let resets = state.pending_resets.load(Ordering::Relaxed);
+        if resets != 0 {
+            self.batch.reset(state, resets);
+            state.pending_resets.fetch_and(!resets, Ordering::Release);
+        }
+
+        // If metering is paused, only keep the JACK clock up to date
+        if state.paused.load(Ordering::Relaxed) {
+            state.update_time(next_time);
+            return;
+        }
+
+        // Feed input frames into the meters, unless a test signal should be
+        // fed in their stead
+        let batch = &mut self.batch;
+        let test_signal = state.test_signal.load(Ordering::Relaxed);
+        match decode_test_signal(test_signal) {
+            None => state.feed_meters(batch, input, current_time, next_time),
+            Some((signal, rms_level)) => {
+                // The test signal is rendered into the scratch buffer, chunk
+                // by chunk if JACK's buffer size grew since activation.
+                self.generator.configure(signal, rms_level, sampling_rate);
+                let len = input.len();
+                let mut offset = 0;
+                while offset < len {
+                    let chunk_len = self.scratch.len().min(len - offset);
+                    let chunk = &mut self.scratch[..chunk_len];
+                    self.generator.fill(chunk);
+                    state.feed_meters(batch,
+                                      chunk,
+                                      interpolate_time(current_time,
+                                                       next_time,
+                                                       offset,
+                                                       len),
+                                      interpolate_time(current_time,
+                                                       next_time,
+                                                       offset + chunk_len,
+                                                       len));
+                    offset += chunk_len;
+                }
+            }
+        }
+
+        // Periodically publish the meters, then update client view of the
+        // JACK clock so that it matches the published meter values
+        if batch.try_publish(state) {
+            state.update_time(next_time);
+        }
+    }
+}
+
+impl ProcessHandler for AudioHandler {
+    // Hook to process incoming audio data
+    fn process(&mut self, client: &Client, scope: &ProcessScope) -> Control {
+        let start = Instant::now();
+        let state = &self.state;
+        let input_port = &self.input_port;
+        let processor = &mut self.processor;
+        // NOTE: A panic can leave the batch in an inconsistent state, but that
+        //       does not matter since the audio thread will then be dead.
+        let result = state.callback_guard(panic::AssertUnwindSafe(|| {
+            // Make sure that the meters are configured for the right sampling
+            // rate, otherwise their ballistics will be wrong.
+            state.check_sampling_rate(client.sample_rate() as u32);
+
+            // Process the input frames of this JACK period
+            let times =
+                scope.cycle_times()
+                     .expect("JACK lib does not seem to support cycle timing");
+            processor.process(state,
+                              input_port.as_slice(scope),
+                              times.current_usecs,
+                              times.next_usecs,
+                              client.sample_rate() as u32);
             Control::Continue
-        })
+        }));
+
+        // Keep track of how much time we spent in the callback
+        state.record_process_time(start.elapsed());
+        result
     }
 }
 
 impl NotificationHandler for JackHandler {
     // Hook to do initialization before an audio thread starts
     fn thread_init(&self, _: &Client) {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
             println!("Audio thread {:?} is ready.",
                      std::thread::current().id());
             Control::Continue
@@ -248,7 +1404,7 @@ impl NotificationHandler for JackHandler {
     //          seen. This function actually shouldn't be marked as safe.
     //
     fn shutdown(&mut self, status: ClientStatus, reason: &str) {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
             // FIXME: Find a way to communicate "status" and "reason" without
             //        calling signal-unsafe functions like malloc or println,
             //        maybe RT-safe logging will also save us here?
@@ -266,9 +1422,13 @@ impl NotificationHandler for JackHandler {
     // 2. Never buffer data based on a system time interval, as that would
     //    require storing an unbounded amount of audio frames. This is an
     //    argument in favor of choosing the JACK clock.
+    // 3. Do not let time-based display ballistics run on the JACK clock while
+    //    it races ahead, see JackInterface::is_freewheeling().
     //
     fn freewheel(&mut self, _: &Client, is_freewheel_enabled: bool) {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
+            self.state.freewheeling.store(is_freewheel_enabled,
+                                          Ordering::Relaxed);
             if is_freewheel_enabled {
                 print!("Entering freewheeling mode. ");
                 println!("JACK clock may go much faster than real time!");
@@ -282,27 +1442,37 @@ impl NotificationHandler for JackHandler {
 
     // Hook to handle JACK buffer size changes
     fn buffer_size(&mut self, _: &Client, size: Frames) -> Control {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
             eprintln!("Buffer size is now: {}", size);
             // NOTE: SamplePeakMeter is unaffected by buffer size
+            // NOTE: QuasiPeakMeter is unaffected by buffer size
             // NOTE: VUMeter is unaffected by buffer size
+            // NOTE: RmsMeter is unaffected by buffer size
+            // NOTE: IntegratedRmsMeter is unaffected by buffer size
+            // NOTE: ZeroCrossingMeter is unaffected by buffer size
             Control::Continue
         })
     }
 
     // Hook to handle JACK sample rate changes
     fn sample_rate(&mut self, _: &Client, srate: Frames) -> Control {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
             eprintln!("Sample rate is now: {}", srate);
             // NOTE: SamplePeakMeter is unaffected by sample rate
-            self.0.loud_meter.update_sampling_rate(srate);
+            self.state.quasi_peak_meter.update_sampling_rate(srate);
+            self.state.loud_meter.update_sampling_rate(srate);
+            self.state.rms_meter.update_sampling_rate(srate);
+            self.state.integrated_rms_meter.update_sampling_rate(srate);
+            self.state.zcr_meter.update_sampling_rate(srate);
+            self.state.sampling_rate.store(srate, Ordering::Release);
             Control::Continue
         })
     }
 
     // Hook to handle audio data loss due to buffer under- or over-run
     fn xrun(&mut self, _: &Client) -> Control {
-        self.callback_guard(|| {
+        self.state.callback_guard(|| {
+            self.state.record_xrun(::jack::get_time());
             eprintln!();
             eprintln!("Audio data was dropped. This should never happen!");
             eprintln!("Either JACK is misconfigured, or our code is wrong.");
@@ -316,4 +1486,631 @@ impl NotificationHandler for JackHandler {
     //
     //       The JACK docs also tell us that as a single-input application, we
     //       do not need a latency update callback.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sampling rate and buffer size used by the tests
+    const SAMPLING_RATE: u32 = 48_000;
+    const BUFFER_SIZE: usize = 256;
+
+    // Generate a 1 kHz sine wave with a given amplitude in dBFS
+    fn tone(amplitude: Decibel, len: usize) -> Vec<Sample> {
+        let amplitude = 10.0f64.powf(amplitude as f64 / 20.0);
+        let omega = 2.0 * std::f64::consts::PI * 1000.0
+                    / (SAMPLING_RATE as f64);
+        (0..len).map(|i| (amplitude * (omega * i as f64).sin()) as Sample)
+                .collect()
+    }
+
+    // Feed some audio into the audio processing path, in JACK periods of
+    // BUFFER_SIZE frames, starting from a given JACK clock timestamp. Returns
+    // the JACK clock timestamp of the end of the audio.
+    fn feed(state: &JackState,
+            processor: &mut AudioProcessor,
+            audio: &[Sample],
+            start_time: Time) -> Time {
+        let period_usecs = (BUFFER_SIZE as u64) * 1_000_000
+                           / (SAMPLING_RATE as u64);
+        let mut time = start_time;
+        for buffer in audio.chunks(BUFFER_SIZE) {
+            processor.process(state,
+                              buffer,
+                              time,
+                              time + period_usecs,
+                              SAMPLING_RATE);
+            time += period_usecs;
+        }
+        time
+    }
+
+    #[test]
+    fn end_to_end_peak() {
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        let end_time = feed(&state,
+                            &mut processor,
+                            &tone(-6.0, SAMPLING_RATE as usize),
+                            0);
+        assert!((state.peak_meter.read_and_reset() + 6.0).abs() < 0.01);
+        assert_eq!(state.next_time(), end_time);
+    }
+
+    // Check that two JackStates have identical shared meter states
+    fn assert_same_meters(a: &JackState, b: &JackState) {
+        assert_eq!(a.peak_meter.serialize_state(),
+                   b.peak_meter.serialize_state());
+        assert_eq!(a.quasi_peak_meter.serialize_state(),
+                   b.quasi_peak_meter.serialize_state());
+        assert_eq!(a.loud_meter.serialize_state(),
+                   b.loud_meter.serialize_state());
+        assert_eq!(a.rms_meter.serialize_state(),
+                   b.rms_meter.serialize_state());
+        assert_eq!(a.integrated_rms_meter.serialize_state(),
+                   b.integrated_rms_meter.serialize_state());
+        assert_eq!(a.zcr_meter.rate_hz(), b.zcr_meter.rate_hz());
+    }
+
+    #[test]
+    fn batched_publication() {
+        // Feed the same audio with and without batching
+        const PUBLISH_PERIOD: u32 = 4;
+        const PUBLICATIONS: usize = 100;
+        let direct = JackState::new(SAMPLING_RATE, 0);
+        let mut direct_processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        let batched = JackState::new(SAMPLING_RATE, 0);
+        let mut batched_processor =
+            AudioProcessor::new(PUBLISH_PERIOD, SAMPLING_RATE, BUFFER_SIZE);
+        let len = PUBLICATIONS * (PUBLISH_PERIOD as usize) * BUFFER_SIZE;
+        let mut audio = tone(-6.0, len / 2);
+        audio.extend(tone(-30.0, len / 2));
+
+        // Batching should only update the shared meters every PUBLISH_PERIOD
+        // JACK cycles, and then publish the same values as direct feeding
+        let mut time = 0;
+        let mut publications = 0;
+        for buffer in audio.chunks(BUFFER_SIZE) {
+            feed(&batched, &mut batched_processor, buffer, time);
+            time = feed(&direct, &mut direct_processor, buffer, time);
+            if batched.next_time() == time {
+                assert_same_meters(&direct, &batched);
+                publications += 1;
+            }
+        }
+        assert_eq!(publications, PUBLICATIONS);
+    }
+
+    #[test]
+    fn publication_count() {
+        // Count the JACK cycles which modify the shared meter state, i.e. the
+        // ones where the audio thread performs atomic stores or CAS on it
+        const PUBLISH_PERIOD: u32 = 4;
+        const PERIODS: usize = 50;
+        let snapshot = |state: &JackState| {
+            (state.peak_meter.serialize_state(),
+             state.quasi_peak_meter.serialize_state(),
+             state.loud_meter.serialize_state(),
+             state.rms_meter.serialize_state(),
+             state.integrated_rms_meter.serialize_state(),
+             state.zcr_meter.serialize_state())
+        };
+        let count_updates = |publish_period| {
+            let state = JackState::new(SAMPLING_RATE, 0);
+            let mut processor =
+                AudioProcessor::new(publish_period, SAMPLING_RATE, BUFFER_SIZE);
+            let len = PERIODS * (PUBLISH_PERIOD as usize) * BUFFER_SIZE;
+            let mut time = 0;
+            let mut updates = 0;
+            for buffer in tone(-6.0, len).chunks(BUFFER_SIZE) {
+                let before = snapshot(&state);
+                time = feed(&state, &mut processor, buffer, time);
+                if snapshot(&state) != before { updates += 1; }
+            }
+            updates
+        };
+
+        // Direct feeding updates the shared meters on every JACK cycle, while
+        // batching only does so once per publish period
+        assert_eq!(count_updates(1), PERIODS * (PUBLISH_PERIOD as usize));
+        assert_eq!(count_updates(PUBLISH_PERIOD), PERIODS);
+    }
+
+    // Connect to a running JACK server, or return None if there is none, in
+    // which case tests that need one should be skipped
+    fn running_server() -> Option<JackInterface> {
+        match JackInterface::new_no_start_server(1) {
+            Ok(interface) => Some(interface),
+            Err(e) => {
+                eprintln!("Skipping test, no JACK server available: {}", e);
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn realtime_status() {
+        // The realtime status matches what JACK reports to other clients
+        if let Some(interface) = running_server() {
+            let (client, _) = Client::new("dbmeter_realtime_check",
+                                          ClientOptions::NO_START_SERVER)
+                                  .expect("Failed to open a second client");
+            // SAFETY: The client pointer is valid while the client is alive
+            let realtime =
+                unsafe { ::jack_sys::jack_is_realtime(client.raw()) != 0 };
+            assert_eq!(interface.is_realtime(), realtime);
+            assert_eq!(interface.server_info().realtime, realtime);
+        }
+    }
+
+    #[test]
+    fn last_buffer_peak() {
+        // The last buffer's peak is available as soon as it is processed, even
+        // if the meters only publish their readings every few JACK periods
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(4, SAMPLING_RATE, BUFFER_SIZE);
+        assert_eq!(state.last_buffer_peak(), Decibel::NEG_INFINITY);
+        let time = feed(&state, &mut processor, &tone(-6.0, BUFFER_SIZE), 0);
+        assert!((state.last_buffer_peak() + 6.0).abs() < 0.01);
+        assert_eq!(state.peak_meter.read_and_reset(), Decibel::NEG_INFINITY);
+
+        // It is not held, so it follows the signal down immediately
+        feed(&state, &mut processor, &tone(-30.0, BUFFER_SIZE), time);
+        assert!((state.last_buffer_peak() + 30.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn port_connections() {
+        if let Some(interface) = running_server() {
+            // Set up a dummy source client with one audio output
+            let (source, _) = Client::new("dbmeter_test_source",
+                                          ClientOptions::NO_START_SERVER)
+                                  .expect("Failed to open a source client");
+            let _output = source.register_port("out", ::jack::AudioOut)
+                                .expect("Failed to register an output");
+            let source_port = format!("{}:out", source.name());
+            let _source = source.activate_async((), ())
+                                .expect("Failed to activate the source");
+
+            // Its output and our input can be found by enumeration...
+            let input_port = interface.input_port_name();
+            let outputs = interface.available_ports(PortFlags::IS_OUTPUT);
+            assert!(outputs.contains(&source_port));
+            assert!(!outputs.iter().any(|port| port == input_port));
+            let inputs = interface.available_ports(PortFlags::IS_INPUT);
+            assert!(inputs.iter().any(|port| port == input_port));
+
+            // ...then connected and disconnected
+            interface.connect(&source_port, input_port)
+                     .expect("Failed to connect the source");
+            interface.disconnect(&source_port, input_port)
+                     .expect("Failed to disconnect the source");
+        }
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        let second = SAMPLING_RATE as usize;
+        let time = feed(&state, &mut processor, &tone(-20.0, second), 0);
+        let loudness = state.loud_meter.read();
+        let rms = state.rms_meter.read();
+
+        // While paused, loud audio does not reach the meters, peak readings
+        // are not reset, and only the JACK clock moves on
+        state.paused.store(true, Ordering::Relaxed);
+        let time = feed(&state, &mut processor, &tone(-3.0, second), time);
+        assert_eq!(state.next_time(), time);
+        assert_eq!(state.frames_processed.load(Ordering::Relaxed),
+                   second as u64);
+        for _ in 0..2 {
+            let (peak, held_peak) = state.read_and_reset_peak_with_hold();
+            assert!((peak + 20.0).abs() < 0.01);
+            assert!((held_peak + 20.0).abs() < 0.01);
+            let quasi_peak = state.read_and_reset_quasi_peak();
+            assert!((quasi_peak + 20.0).abs() < 0.5);
+        }
+        assert_eq!(state.loud_meter.read(), loudness);
+        assert_eq!(state.rms_meter.read(), rms);
+
+        // After resuming, the meters follow the input again
+        state.paused.store(false, Ordering::Relaxed);
+        feed(&state, &mut processor, &tone(-3.0, second), time);
+        let (peak, held_peak) = state.read_and_reset_peak_with_hold();
+        assert!((peak + 3.0).abs() < 0.01);
+        assert!((held_peak + 3.0).abs() < 0.01);
+        assert!((state.read_and_reset_quasi_peak() + 3.0).abs() < 0.5);
+        assert!(state.loud_meter.read() > loudness + 16.0);
+        assert!(state.rms_meter.read() > rms + 16.0);
+    }
+
+    #[test]
+    fn process_time() {
+        // Time actual audio processing, like the JACK process handler does
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        let buffer = tone(-20.0, BUFFER_SIZE);
+        for i in 0..100 {
+            let start = Instant::now();
+            processor.process(&state, &buffer, i * 1000, (i + 1) * 1000,
+                              SAMPLING_RATE);
+            state.record_process_time(start.elapsed());
+        }
+
+        // Durations are positive, the average is below the maximum, and
+        // processing a buffer takes much less than a second
+        let time = state.query_process_time();
+        assert!(time.average > 0.0);
+        assert!(time.average <= time.max);
+        assert!(time.max < 1_000_000.0);
+
+        // The maximum is reset by queries, but not the average
+        let next_time = state.query_process_time();
+        assert_eq!(next_time, ProcessTime { max: 0.0, ..time });
+
+        // The average moves slowly towards new durations, while the maximum
+        // jumps to them immediately
+        state.record_process_time(Duration::from_millis(100));
+        let slow_time = state.query_process_time();
+        assert_eq!(slow_time.max, 100_000.0);
+        assert!(slow_time.average > time.average);
+        assert!(slow_time.average < 0.02 * 100_000.0 + time.average);
+    }
+
+    #[test]
+    fn staleness() {
+        // Meters are fresh right after an update...
+        let state = JackState::new(SAMPLING_RATE, 1_000);
+        let mut processor =
+            AudioProcessor::new(2, SAMPLING_RATE, BUFFER_SIZE);
+        let time = feed(&state,
+                        &mut processor,
+                        &tone(-20.0, 2 * BUFFER_SIZE),
+                        1_000);
+        assert_eq!(state.time_since_update(time), 0);
+        assert_eq!(state.time_since_update(time - 10), 0);
+
+        // ...then get staler as time passes without updates, including JACK
+        // periods whose meter updates were not published yet...
+        assert_eq!(state.time_since_update(time + 500), 500);
+        let next_time = feed(&state,
+                             &mut processor,
+                             &tone(-20.0, BUFFER_SIZE),
+                             time);
+        assert_eq!(state.time_since_update(next_time), next_time - time);
+
+        // ...but paused meters are kept up to date
+        state.paused.store(true, Ordering::Relaxed);
+        let time = feed(&state,
+                        &mut processor,
+                        &tone(-20.0, BUFFER_SIZE),
+                        next_time);
+        assert_eq!(state.time_since_update(time), 0);
+    }
+
+    #[test]
+    fn frames_processed() {
+        // Every frame is counted, including those of a last partial buffer,
+        // whether meter updates are published or not
+        for &publish_period in &[1, 3] {
+            let state = JackState::new(SAMPLING_RATE, 0);
+            let mut processor =
+                AudioProcessor::new(publish_period, SAMPLING_RATE, BUFFER_SIZE);
+            assert_eq!(state.frames_processed(), 0);
+            let frames = 10 * BUFFER_SIZE + 17;
+            feed(&state, &mut processor, &tone(-20.0, frames), 0);
+            assert_eq!(state.frames_processed(), frames as u64);
+        }
+    }
+
+    #[test]
+    fn sampling_rate_change_in_progress() {
+        // JACK reports a new sampling rate, but the sample_rate() callback
+        // did not reconfigure the meters yet: this is fine
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let result = state.callback_guard(|| {
+            state.check_sampling_rate(2 * SAMPLING_RATE);
+            Control::Continue
+        });
+        assert_eq!(result, Control::Continue);
+        assert!(state.is_alive());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn sampling_rate_mismatch() {
+        // The sample_rate() callback claims to be done, but a meter was left
+        // at the old sampling rate: this should kill the audio thread
+        let state = JackState::new(SAMPLING_RATE, 0);
+        state.sampling_rate.store(2 * SAMPLING_RATE, Ordering::Relaxed);
+        let result = state.callback_guard(|| {
+            state.check_sampling_rate(2 * SAMPLING_RATE);
+            Control::Continue
+        });
+        assert_eq!(result, Control::Quit);
+        assert!(!state.is_alive());
+    }
+
+    #[test]
+    fn meter_sampling_rates() {
+        // Meters are set up for the sampling rate that they are given...
+        let state = JackState::new(44_100, 0);
+        state.check_sampling_rate(44_100);
+        assert_eq!(state.loud_meter.sampling_rate(), 44_100);
+        assert_eq!(state.zcr_meter.sampling_rate(), 44_100);
+
+        // ...which is the one that the JACK client reports
+        if let Some(interface) = running_server() {
+            let sampling_rate = interface.sampling_rate();
+            let state = &interface.state;
+            assert_eq!(state.sampling_rate.load(Ordering::Acquire),
+                       sampling_rate);
+            assert_eq!(state.quasi_peak_meter.sampling_rate(), sampling_rate);
+            assert_eq!(state.loud_meter.sampling_rate(), sampling_rate);
+            assert_eq!(state.rms_meter.sampling_rate(), sampling_rate);
+            assert_eq!(state.integrated_rms_meter.sampling_rate(),
+                       sampling_rate);
+            assert_eq!(state.zcr_meter.sampling_rate(), sampling_rate);
+        }
+    }
+
+    #[test]
+    fn reset_during_batch() {
+        // Stop feeding loud audio in the middle of a batch
+        const PUBLISH_PERIOD: u32 = 4;
+        let buffers = 100 * (PUBLISH_PERIOD as usize) + 2;
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(PUBLISH_PERIOD, SAMPLING_RATE, BUFFER_SIZE);
+        let time = feed(&state,
+                        &mut processor,
+                        &tone(-6.0, buffers * BUFFER_SIZE),
+                        0);
+
+        // Request a reset, then finish the batch with quieter audio
+        state.request_resets(RESET_INTEGRATED_RMS);
+        let quiet = tone(-20.0, buffers * BUFFER_SIZE);
+        let end_time = feed(&state, &mut processor, &quiet, time);
+        assert_eq!(state.next_time(), end_time);
+        assert_eq!(state.pending_resets.load(Ordering::Relaxed), 0);
+
+        // Loud audio from the interrupted batch should not have been published
+        // after the reset
+        let integrated_rms = state.integrated_rms_meter.read();
+        assert!((integrated_rms + 23.0).abs() < 0.2,
+                "Integrated RMS level is {} dBFS", integrated_rms);
+    }
+
+    #[test]
+    fn client_status() {
+        // Opening a client may start the server or rename the client, which
+        // is fine, and the former is reported
+        assert!(!check_client_status(ClientStatus::empty()).unwrap());
+        assert!(!check_client_status(ClientStatus::NAME_NOT_UNIQUE).unwrap());
+        assert!(check_client_status(ClientStatus::SERVER_STARTED).unwrap());
+        assert!(check_client_status(ClientStatus::SERVER_STARTED
+                                    | ClientStatus::NAME_NOT_UNIQUE).unwrap());
+
+        // Failures are reported as errors, without the harmless status bits
+        let status = ClientStatus::FAILURE
+                     | ClientStatus::SERVER_FAILED
+                     | ClientStatus::SERVER_STARTED;
+        match check_client_status(status) {
+            Err(Error::Jack(::jack::Error::ClientError(bad_status))) => {
+                assert_eq!(bad_status,
+                           ClientStatus::FAILURE | ClientStatus::SERVER_FAILED)
+            }
+            other => panic!("Unexpected status check result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn started_server() {
+        // We were not allowed to start the server, so we did not
+        if let Some(interface) = running_server() {
+            assert!(!interface.started_server());
+        }
+    }
+
+    #[test]
+    fn xrun_recovery() {
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        let second = SAMPLING_RATE as usize;
+        let time = feed(&state, &mut processor, &tone(-20.0, second), 0);
+        assert!(!state.had_recent_xrun());
+
+        // Readings are flagged during the recovery time after an xrun...
+        state.record_xrun(time);
+        assert!(state.had_recent_xrun());
+        assert_eq!(state.xruns.load(Ordering::Relaxed), 1);
+        let time = feed(&state, &mut processor, &tone(-20.0, second / 2), time);
+        assert!(state.had_recent_xrun());
+
+        // ...and not after that
+        feed(&state, &mut processor, &tone(-20.0, second), time);
+        assert!(!state.had_recent_xrun());
+        assert_eq!(state.xruns.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn server_not_running() {
+        // Without a JACK server, we must get a specific error, and no attempt
+        // to start one. This can only be checked when no server is running.
+        match JackInterface::new_no_start_server(1) {
+            Ok(_) => eprintln!("Skipping test, a JACK server is running"),
+            Err(Error::ServerNotRunning) => {}
+            Err(e) => panic!("Unexpected error {}", e),
+        }
+    }
+
+    #[test]
+    fn session_report() {
+        // Start a session in the middle of a batch of loud audio
+        const PUBLISH_PERIOD: u32 = 4;
+        let buffers = 100 * (PUBLISH_PERIOD as usize) + 2;
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(PUBLISH_PERIOD, SAMPLING_RATE, BUFFER_SIZE);
+        let time = feed(&state,
+                        &mut processor,
+                        &tone(-6.0, buffers * BUFFER_SIZE),
+                        0);
+        state.xruns.store(3, Ordering::Relaxed);
+        state.request_resets(RESET_SESSION);
+
+        // Measure some quieter audio during the session
+        let quiet = tone(-20.0, buffers * BUFFER_SIZE);
+        feed(&state, &mut processor, &quiet, time);
+        state.xruns.store(4, Ordering::Relaxed);
+
+        // The report should only account for the audio of the session
+        let report = state.session_report(SAMPLING_RATE, 3);
+        assert_eq!(report.duration_secs,
+                   quiet.len() as f64 / SAMPLING_RATE as f64);
+        assert!((report.max_peak + 20.0).abs() < 0.01,
+                "Max peak is {} dBFS", report.max_peak);
+        assert!((report.integrated_rms + 23.0).abs() < 0.2,
+                "Integrated RMS level is {} dBFS", report.integrated_rms);
+        assert_eq!(report.xruns, 1);
+    }
+
+    #[test]
+    fn freewheeling_session() {
+        // When JACK is freewheeling, audio is processed much faster than the
+        // JACK clock advances, here 10s of audio in about 1s
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        state.request_resets(RESET_SESSION);
+        let audio = tone(-20.0, 10 * SAMPLING_RATE as usize);
+        let period_usecs = (BUFFER_SIZE * 1_000_000 / audio.len()) as Time;
+        let mut time = 0;
+        for buffer in audio.chunks(BUFFER_SIZE) {
+            processor.process(&state,
+                              buffer,
+                              time,
+                              time + period_usecs,
+                              SAMPLING_RATE);
+            time += period_usecs;
+        }
+
+        // Measurement durations should follow the audio, not the JACK clock
+        let report = state.session_report(SAMPLING_RATE, 0);
+        assert_eq!(report.duration_secs, 10.0);
+        let clock_secs = units::usecs_to_secs(state.next_time());
+        assert!((clock_secs - 1.0).abs() < 0.01,
+                "JACK clock advanced by {}s", clock_secs);
+    }
+
+    #[test]
+    fn time_conversions() {
+        if let Some(interface) = running_server() {
+            let sampling_rate = interface.sampling_rate() as u64;
+            assert_eq!(interface.frames_to_secs(3 * sampling_rate), 3.0);
+            assert_eq!(interface.usecs_to_secs(1_500_000), 1.5);
+        }
+    }
+
+    #[test]
+    fn test_signal_encoding() {
+        assert_eq!(decode_test_signal(NO_TEST_SIGNAL), None);
+        for &signal in &[TestSignal::Tone, TestSignal::PinkNoise] {
+            for &level in &[-20.0, 0.0, 3.5] {
+                assert_eq!(decode_test_signal(encode_test_signal(signal,
+                                                                 level)),
+                           Some((signal, level)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_signal() {
+        // Use a scratch buffer smaller than the JACK buffers, as if the JACK
+        // buffer size had grown since activation
+        let state = JackState::new(SAMPLING_RATE, 0);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE / 3);
+        for &signal in &[TestSignal::Tone, TestSignal::PinkNoise] {
+            state.integrated_rms_meter.reset();
+            state.test_signal.store(encode_test_signal(signal, -20.0),
+                                    Ordering::Relaxed);
+            let silence = vec![0.0; 5 * SAMPLING_RATE as usize];
+            feed(&state, &mut processor, &silence, 0);
+            let level = state.integrated_rms_meter.read();
+            assert!((level + 20.0).abs() < 0.2,
+                    "{:?} read {} dBFS", signal, level);
+            assert_eq!(state.frames_processed.load(Ordering::Relaxed),
+                       silence.len() as u64);
+            state.frames_processed.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn max_peak_encoding() {
+        for &(peak, time) in &[(1.0, 0), (0.5, 123_456_789), (0.123, 42)] {
+            let (peak_db, decoded_time) =
+                decode_max_peak(encode_max_peak(peak, time));
+            let actual_db = 20.0 * peak.log10();
+            assert_eq!(decoded_time, time);
+            assert!(peak_db >= actual_db);
+            assert!(peak_db - actual_db < 0.001);
+        }
+        assert_eq!(decode_max_peak(NO_MAX_PEAK).0, Decibel::NEG_INFINITY);
+        assert_eq!(decode_max_peak(encode_max_peak(1.0, Time::MAX)).1,
+                   MAX_PEAK_TIME_MASK);
+    }
+
+    #[test]
+    fn max_peak_tracking() {
+        // Feed a transient in the middle of a JACK period
+        const START_TIME: Time = 1_000_000;
+        let state = JackState::new(SAMPLING_RATE, START_TIME);
+        let mut processor =
+            AudioProcessor::new(1, SAMPLING_RATE, BUFFER_SIZE);
+        assert_eq!(state.max_peak_with_time(), (Decibel::NEG_INFINITY, 0));
+        let transient = |amplitude| {
+            let mut buffer = vec![0.0; BUFFER_SIZE];
+            buffer[BUFFER_SIZE / 2] = amplitude;
+            buffer
+        };
+        let mut time =
+            feed(&state, &mut processor, &transient(0.5), START_TIME);
+        let half_period = (time - START_TIME) / 2;
+        let (peak, peak_time) = state.max_peak_with_time();
+        assert!((peak - 20.0 * 0.5f32.log10()).abs() < 0.001);
+        assert_eq!(peak_time, START_TIME + half_period);
+
+        // A later, larger transient should update both value and timestamp
+        let larger_time = time;
+        time = feed(&state, &mut processor, &transient(0.8), time);
+        let (peak, peak_time) = state.max_peak_with_time();
+        assert!((peak - 20.0 * 0.8f32.log10()).abs() < 0.001);
+        assert_eq!(peak_time, larger_time + half_period);
+
+        // A later, smaller transient should update neither
+        feed(&state, &mut processor, &transient(0.6), time);
+        assert_eq!(state.max_peak_with_time(), (peak, peak_time));
+
+        // The measurement report should use the same held peak
+        assert_eq!(state.session_report(SAMPLING_RATE, 0).max_peak, peak);
+    }
+
+    #[test]
+    fn server_info() {
+        if let Some(interface) = running_server() {
+            let info = interface.server_info();
+            assert_eq!(info.sampling_rate, interface.sampling_rate());
+            assert!(info.buffer_size > 0);
+            assert!(info.cpu_load >= 0.0);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use crate::Decibel;
+
+
+// An LED ladder, as found on classic hardware meters
+//
+// Such meters do not display a continuous bar, but a set of discrete LED
+// segments, each of which lights up when the level reaches a certain dB
+// threshold. This maps meter readings to a number of lit segments, which can
+// be used to drive LED-style user interfaces or actual LED hardware.
+//
+pub struct LedLadder {
+    // Level at which each segment lights up, in increasing order
+    thresholds: Box<[Decibel]>,
+}
+
+impl LedLadder {
+    // Build an LED ladder from the thresholds of its segments
+    pub fn new(thresholds: impl Into<Box<[Decibel]>>) -> Self {
+        let thresholds = thresholds.into();
+        assert!(thresholds.windows(2).all(|w| w[0] < w[1]),
+                "LED thresholds must be sorted in strictly increasing order");
+        Self { thresholds }
+    }
+
+    // Total number of segments in the ladder
+    pub fn num_segments(&self) -> usize {
+        self.thresholds.len()
+    }
+
+    // Number of segments that are lit by a certain level
+    //
+    // Levels below the lowest threshold (including -inf and NaN) light up no
+    // segment, levels above the highest threshold light up every segment.
+    //
+    pub fn lit_segments(&self, level: Decibel) -> usize {
+        self.thresholds.iter()
+                       .take_while(|&&threshold| level >= threshold)
+                       .count()
+    }
+}
+
+impl Default for LedLadder {
+    // Use a typical hardware peak meter's ladder
+    fn default() -> Self {
+        Self::new(vec![-40.0, -30.0, -20.0, -12.0, -6.0, -3.0, 0.0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lit_segments() {
+        let ladder = LedLadder::default();
+        assert_eq!(ladder.num_segments(), 7);
+
+        // Below the lowest threshold, including silence and garbage
+        assert_eq!(ladder.lit_segments(-60.0), 0);
+        assert_eq!(ladder.lit_segments(Decibel::NEG_INFINITY), 0);
+        assert_eq!(ladder.lit_segments(Decibel::NAN), 0);
+
+        // Segments light up as soon as their threshold is reached
+        assert_eq!(ladder.lit_segments(-40.0), 1);
+        assert_eq!(ladder.lit_segments(-25.0), 2);
+        assert_eq!(ladder.lit_segments(-12.0), 4);
+        assert_eq!(ladder.lit_segments(-0.1), 6);
+
+        // Above the highest threshold, everything is lit
+        assert_eq!(ladder.lit_segments(0.0), 7);
+        assert_eq!(ladder.lit_segments(6.0), 7);
+        assert_eq!(ladder.lit_segments(Decibel::INFINITY), 7);
+    }
+}
\ No newline at end of file
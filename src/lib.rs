@@ -0,0 +1,20 @@
+pub mod alarm;
+pub mod ballistics;
+pub mod bar;
+pub mod calibration;
+pub mod classifier;
+pub mod error;
+pub mod generator;
+#[cfg(feature = "jack-backend")]
+pub mod jack;
+pub mod leds;
+pub mod meters;
+pub mod units;
+
+
+// Just a few typedefs to clarify things
+pub type Sample = f32;
+pub type Decibel = f32;
+
+// Timestamps and durations in microseconds, as used by the JACK clock
+pub type Time = u64;
\ No newline at end of file
@@ -1,27 +1,143 @@
-mod jack;
-mod meters;
+use dbmeter::{
+    alarm::{AlarmKind, LevelAlarm},
+    ballistics::{DecayingPeak, DecayRate},
+    bar::{Bar, Zones},
+    calibration,
+    classifier::SignalClassifier,
+    jack::JackInterface,
+    leds::LedLadder,
+    units::secs_to_usecs,
+};
 
-use crate::jack::JackInterface;
+use ::jack::PortFlags;
 
 
-// Just a few typedefs to clarify things
-pub type Sample = f32;
-pub type Decibel = f32;
+// Publish meter readings every N JACK cycles (increase for tiny buffers)
+const PUBLISH_PERIOD: u32 = 1;
 
 // FIXME: Replace (e)println with RT-safe logging everywhere
 fn main() {
     // Set up the audio work
-    let jack_interface = JackInterface::new();
+    let jack_interface = JackInterface::new(PUBLISH_PERIOD)
+                                       .expect("Failed to set up JACK");
+    if !jack_interface.is_realtime() {
+        eprintln!("JACK is not running in realtime mode, expect xruns.");
+    }
+    if jack_interface.started_server() {
+        println!("The JACK server was started for us.");
+    }
+    let server_info = jack_interface.server_info();
+    println!("JACK is running at {} Hz with {} frames per period.",
+             server_info.sampling_rate, server_info.buffer_size);
+
+    // Make sure that the meters read a known calibration tone correctly
+    for check in calibration::self_test(jack_interface.sampling_rate()) {
+        if check.passed() {
+            println!("Calibration of {} meter: PASS", check.meter);
+        } else {
+            eprintln!("Calibration of {} meter: FAIL (read {} {}, expected \
+                       {} {})",
+                      check.meter, check.measured, check.unit,
+                      check.expected, check.unit);
+        }
+    }
+
+    // Connect the JACK ports specified on the command line to our input, or
+    // tell the user which ports are available if there are none.
+    let sources = std::env::args().skip(1).collect::<Vec<_>>();
+    if sources.is_empty() {
+        println!("No input specified. Available JACK audio outputs are:");
+        for port in jack_interface.available_ports(PortFlags::IS_OUTPUT) {
+            println!("- {}", port);
+        }
+    }
+    for source in sources {
+        jack_interface.connect(&source, jack_interface.input_port_name())
+                      .expect("Failed to connect input port");
+    }
+
+    // Watch out for signal loss and sustained near-clipping levels
+    let mut signal_loss_alarm =
+        LevelAlarm::new(AlarmKind::UnderLevel, -60.0, 3.0, secs_to_usecs(5.0));
+    signal_loss_alarm.set_callback(|_kind, tripped| {
+        if tripped {
+            eprintln!("WARNING: Audio input seems to have been lost!");
+        } else {
+            eprintln!("Audio input is back.");
+        }
+    });
+    let mut over_level_alarm =
+        LevelAlarm::new(AlarmKind::OverLevel, -1.0, 1.0, secs_to_usecs(1.0));
+
+    // Display peaks like a hardware LED meter would
+    let led_ladder = LedLadder::default();
+    let mut decaying_peak = DecayingPeak::new(DecayRate::BbcPpm);
+
+    // Display the VU-meter as a continuous bar
+    const VU_BAR_LENGTH: usize = 40;
+    let vu_bar = Bar::default();
+    let vu_zones = Zones::for_terminal();
+
+    // Tell what kind of signal is on the input
+    let classifier = SignalClassifier::default();
 
     // TODO: Display Real Pretty graphics, not console prints
     loop {
         std::thread::sleep(std::time::Duration::from_millis(300));
         assert!(jack_interface.is_alive(), "Audio thread has died");
-        eprintln!("Audio peak during last period: {} dBFS",
-                  jack_interface.read_and_reset_peak());
-        eprintln!("Current audio loudness: {} VUFS",
-                  jack_interface.read_loudness());
-        eprintln!("Jack clock at end of last processed frame: {:?} µs",
-                  jack_interface.next_time());
+        let time = jack_interface.next_time();
+        let staleness = jack_interface.time_since_update_us();
+        if jack_interface.usecs_to_secs(staleness) > 1.0 {
+            eprintln!("WARNING: Audio thread has stalled, readings are stale!");
+        }
+        if jack_interface.had_recent_xrun() {
+            eprintln!("WARNING: Audio data was recently dropped, readings may \
+                       be inaccurate!");
+        }
+        let (peak, held_peak) = jack_interface.read_and_reset_peak_with_hold();
+        let loudness = jack_interface.read_loudness();
+        let quasi_peak = jack_interface.read_and_reset_quasi_peak();
+        let display_peak =
+            decaying_peak.update(quasi_peak,
+                                 time,
+                                 jack_interface.is_freewheeling());
+        let lit_leds = led_ladder.lit_segments(display_peak);
+        eprintln!("Audio peak during last period: {} dBFS [{}{}]",
+                  peak,
+                  "#".repeat(lit_leds),
+                  " ".repeat(led_ladder.num_segments() - lit_leds));
+        eprintln!("Highest audio peak so far: {} dBFS", held_peak);
+        eprintln!("Audio quasi-peak during last period: {} dBFS", quasi_peak);
+        eprintln!("Audio peak during last buffer: {} dBFS",
+                  jack_interface.last_buffer_peak());
+        eprintln!("Current audio loudness: {} VUFS [{}] (peak marker: {} \
+                   VUFS)",
+                  loudness,
+                  vu_bar.render(loudness, VU_BAR_LENGTH, vu_zones.as_ref()),
+                  jack_interface.read_loudness_peak_hold());
+        let rms = jack_interface.read_rms();
+        eprintln!("Current audio RMS level: {} dBFS", rms);
+        eprintln!("Integrated RMS level so far: {} dBFS",
+                  jack_interface.read_integrated_rms());
+        let zero_crossing_rate = jack_interface.zero_crossing_rate();
+        eprintln!("Zero-crossing rate: {} Hz", zero_crossing_rate);
+        eprintln!("Input signal looks like: {:?}",
+                  classifier.classify(peak, rms, zero_crossing_rate));
+        eprintln!("Jack clock at end of last processed frame: {:?} µs", time);
+        eprintln!("Audio frames processed so far: {}",
+                  jack_interface.frames_processed());
+        let process_time = jack_interface.process_time_us();
+        eprintln!("Audio processing time: {} µs on average, {} µs at most",
+                  process_time.average, process_time.max);
+
+        // Keep the alarms quiet while the meters are warming up
+        let warmed_up = jack_interface.warmed_up();
+        signal_loss_alarm.set_armed(warmed_up);
+        over_level_alarm.set_armed(warmed_up);
+        signal_loss_alarm.update(loudness, time);
+        over_level_alarm.update(peak, time);
+        if over_level_alarm.is_tripped() {
+            eprintln!("WARNING: Audio peaks have been too close to clipping!");
+        }
     }
 }
\ No newline at end of file
@@ -1,8 +1,85 @@
 use atomic::{Atomic, Ordering};
-use crate::{Decibel, Sample};
+use crate::{Decibel, Sample, ballistics::DecayRate};
 use std::panic::{RefUnwindSafe, UnwindSafe};
 
 
+// Common interface to all meters, for code which handles them generically
+//
+// This trait is object-safe, so that heterogeneous collections of meters can
+// be built, e.g. as Vec<Box<dyn Meter + Sync>>. All meters use interior
+// mutability, so they can be read concurrently via shared references, and
+// most of them can also be fed concurrently. The exceptions are
+// IntegratedRmsMeter and Decimated, which must only be fed by one thread at a
+// time. For IntegratedRmsMeter, reset() counts as feeding, and can be lost if
+// it is called while another thread is feeding it. VUMeter can be fed
+// concurrently, but its peak hold overlay may then miss some peaks.
+//
+pub trait Meter {
+    // Set up a meter with default settings for a given sampling rate
+    //
+    // All meters can be built this way, even those which do not care about the
+    // sampling rate, so that code which builds meters generically does not need
+    // to know which meters depend on it.
+    //
+    fn new_for(sampling_rate: u32) -> Self where Self: Sized;
+
+    // Feed a slice of audio samples into the meter
+    fn integrate_slice(&self, data: &[Sample]);
+
+    // Query the current value of the meter, in dB (or in crossings per second
+    // for ZeroCrossingMeter, whose readings are not levels)
+    fn read(&self) -> Decibel;
+
+    // Reset the meter to its initial state
+    fn reset(&self);
+
+    // Query the current configuration of the meter, e.g. for display labels
+    fn config(&self) -> MeterConfig;
+}
+
+// Snapshot of a meter's configuration, as reported by Meter::config()
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeterConfig {
+    // Sample peak meter, with the polarity of the peaks that it tracks
+    SamplePeak {
+        polarity: PeakPolarity,
+    },
+
+    // Quasi-peak meter, with its sampling rate and attack time (in seconds)
+    QuasiPeak {
+        sampling_rate: u32,
+        attack_time: f32,
+    },
+
+    // VU-meter, with its sampling rate, amplitude correction, the level that
+    // reads as 0 VU (in dBFS), and the ballistics of its peak hold overlay
+    VU {
+        sampling_rate: u32,
+        correction: AmplitudeCorrection,
+        reference: Decibel,
+        peak_hold: PeakHold,
+    },
+
+    // RMS meter, with its sampling rate and time weighting
+    Rms {
+        sampling_rate: u32,
+        time_weighting: TimeWeighting,
+    },
+
+    // Integrated RMS meter, with its sampling rate and the level that
+    // read_relative() is relative to (in dBFS)
+    IntegratedRms {
+        sampling_rate: u32,
+        reference: Decibel,
+    },
+
+    // Zero-crossing rate meter, with its sampling rate
+    ZeroCrossing {
+        sampling_rate: u32,
+    },
+}
+
+
 // A basic peak meter meant for interactive displays
 //
 // Uses the highest sample in the audio data as the peak value. Beware, this
@@ -16,29 +93,136 @@ use std::panic::{RefUnwindSafe, UnwindSafe};
 // one later, and then this one will forever become a demo toy.
 //
 pub struct SamplePeakMeter {
-    // Current peak value, as an FP sample
-    peak_sample: Atomic<Sample>,
+    // Current peak values, as FP samples
+    peaks: Atomic<PeakSamples>,
+
+    // Polarity of the peaks that are tracked
+    polarity: PeakPolarity,
+
+    // Number of non-finite samples or states which had to be discarded
+    sanitized_events: Atomic<u64>,
+}
+
+// The peak values that are tracked by SamplePeakMeter
+//
+// They are packed together so that they can be updated and read atomically,
+// and aligned so that this can be done without locking on 64-bit CPUs.
+//
+#[derive(Clone, Copy)]
+#[repr(C, align(8))]
+struct PeakSamples {
+    // Highest sample since the last read_and_reset()
+    window: Sample,
+
+    // Highest sample since the peak meter was created or reset_hold() was
+    // last called, which is never lower than the window peak
+    held: Sample,
+}
+
+// Polarity of the excursions that a SamplePeakMeter tracks
+//
+// Most of the time, positive and negative excursions matter equally, but some
+// measurement contexts only care about one of them (e.g. asymmetrical clipping
+// of a DC-coupled device).
+//
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PeakPolarity {
+    // Track the absolute value of samples (this is the default)
+    #[default]
+    Absolute,
+
+    // Only track positive excursions, negative samples read as silence
+    Positive,
+
+    // Only track negative excursions, positive samples read as silence
+    Negative,
+}
+
+impl PeakPolarity {
+    // Magnitude of a sample's excursion in this polarity
+    fn magnitude(self, spl: Sample) -> Sample {
+        match self {
+            PeakPolarity::Absolute => spl.abs(),
+            PeakPolarity::Positive => spl.max(0.0),
+            PeakPolarity::Negative => (-spl).max(0.0),
+        }
+    }
 }
 
 impl SamplePeakMeter {
     // Create a sample-based peak-meter
     pub fn new() -> Self {
-        Self { peak_sample: Atomic::new(0.0) }
+        Self::with_polarity(PeakPolarity::default())
+    }
+
+    // Create a sample-based peak-meter which only tracks excursions of a
+    // certain polarity
+    pub fn with_polarity(polarity: PeakPolarity) -> Self {
+        Self {
+            peaks: Atomic::new(PeakSamples { window: 0.0, held: 0.0 }),
+            polarity,
+            sanitized_events: Atomic::new(0),
+        }
+    }
+
+    // Query the polarity of the excursions that the peak meter tracks
+    pub fn polarity(&self) -> PeakPolarity {
+        self.polarity
     }
 
     // Feed new data into the peak meter
     pub fn integrate(&self, data: impl IntoIterator<Item=Sample>) {
-        let max = data.into_iter()
-                      .map(|x| x.abs())
-                      .fold(0.0f32, |x, y| x.max(y));
-        let mut old_max = self.peak_sample.load(Ordering::Relaxed);
-        while max > old_max {
-            match self.peak_sample.compare_exchange(old_max,
-                                                    max,
-                                                    Ordering::Relaxed,
-                                                    Ordering::Relaxed) {
+        let mut max = self.begin_batch();
+        self.integrate_batch(&mut max, data);
+        self.end_batch(max);
+    }
+
+    // Start accumulating data locally, without touching the shared peak value
+    //
+    // This is meant for callers which want to integrate several buffers of
+    // audio data before publishing the result, in order to reduce atomic
+    // traffic. See integrate_batch() and end_batch() for the rest.
+    //
+    pub fn begin_batch(&self) -> Sample {
+        0.0
+    }
+
+    // Feed new data into a local peak accumulator
+    //
+    // Non-finite samples are skipped and counted, as an infinite peak would
+    // otherwise stick until the next reset, and NaNs have no magnitude.
+    //
+    pub fn integrate_batch(&self,
+                           max: &mut Sample,
+                           data: impl IntoIterator<Item=Sample>) {
+        let polarity = self.polarity;
+        let mut discarded = 0;
+        *max = data.into_iter()
+                   .filter(|x| {
+                       let finite = x.is_finite();
+                       if !finite { discarded += 1; }
+                       finite
+                   })
+                   .map(|x| polarity.magnitude(x))
+                   .fold(*max, |x, y| x.max(y));
+        if discarded > 0 { self.record_sanitized(discarded); }
+    }
+
+    // Publish the result of local peak accumulation
+    pub fn end_batch(&self, max: Sample) {
+        let mut old_peaks = self.peaks.load(Ordering::Relaxed);
+        // NOTE: No need to check the held peak, it's never below the window one
+        while max > old_peaks.window {
+            let new_peaks = PeakSamples {
+                window: max,
+                held: old_peaks.held.max(max),
+            };
+            match self.peaks.compare_exchange(old_peaks,
+                                              new_peaks,
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
                 Ok(_) => return,
-                Err(new_old_max) => old_max = new_old_max,
+                Err(new_old_peaks) => old_peaks = new_old_peaks,
             }
         }
     }
@@ -46,7 +230,139 @@ impl SamplePeakMeter {
 
     // Query the current value of the peak meter in dBFS and reset it to zero
     pub fn read_and_reset(&self) -> Decibel {
-        20.0 * self.peak_sample.swap(0., Ordering::Relaxed).log10()
+        self.read_and_reset_with_hold().0
+    }
+
+    // Query the current value of the peak meter and the held peak value (the
+    // highest peak since the meter was created or the hold was last reset) in
+    // dBFS, then reset the former
+    //
+    // Both values are read at once, so the held peak is guaranteed to be
+    // higher than or equal to the current peak.
+    //
+    pub fn read_and_reset_with_hold(&self) -> (Decibel, Decibel) {
+        let old_peaks =
+            self.update_peaks(|peaks| PeakSamples { window: 0.0, ..peaks });
+        Self::peaks_to_dbfs(old_peaks)
+    }
+
+    // Like read_and_reset_with_hold(), but without resetting anything
+    pub fn read_with_hold(&self) -> (Decibel, Decibel) {
+        Self::peaks_to_dbfs(self.peaks.load(Ordering::Relaxed))
+    }
+
+    // Query the held peak value in dBFS
+    //
+    // Unlike the current peak value, this is never reset by reads, only by
+    // explicit calls to reset_hold(). So it can be used to display the highest
+    // peak since the start of a session.
+    //
+    pub fn read_hold(&self) -> Decibel {
+        self.read_with_hold().1
+    }
+
+    // Reset the held peak value, leaving the current peak value alone
+    pub fn reset_hold(&self) {
+        self.update_peaks(|peaks| PeakSamples { held: peaks.window, ..peaks });
+    }
+
+    // Count some more discarded samples or states
+    fn record_sanitized(&self, count: u64) {
+        self.sanitized_events.fetch_add(count, Ordering::Relaxed);
+    }
+
+    // Query how many non-finite samples had to be discarded, which is a sign
+    // of a misbehaving audio source. Restoring a non-finite state also counts.
+    pub fn sanitized_events(&self) -> u64 {
+        self.sanitized_events.load(Ordering::Relaxed)
+    }
+
+    // Atomically update the peak values, return the previous ones
+    fn update_peaks<F>(&self, update: F) -> PeakSamples
+        where F: Fn(PeakSamples) -> PeakSamples
+    {
+        let mut old_peaks = self.peaks.load(Ordering::Relaxed);
+        loop {
+            match self.peaks.compare_exchange(old_peaks,
+                                              update(old_peaks),
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
+                Ok(_) => return old_peaks,
+                Err(new_old_peaks) => old_peaks = new_old_peaks,
+            }
+        }
+    }
+
+    // Save the internal state of the peak meter, e.g. to resume a measurement
+    // after a restart with restore_state()
+    pub fn serialize_state(&self) -> SamplePeakState {
+        let peaks = self.peaks.load(Ordering::Relaxed);
+        SamplePeakState { window: peaks.window, held: peaks.held }
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // Non-finite peak values are reset, as they would never recover, and the
+    // held peak is raised to the window peak if it was lower.
+    //
+    pub fn restore_state(&self, state: SamplePeakState) {
+        let mut peaks = PeakSamples {
+            window: state.window,
+            held: state.held.max(state.window),
+        };
+        if !(peaks.window.is_finite() && peaks.held.is_finite()) {
+            peaks = PeakSamples { window: 0.0, held: 0.0 };
+            self.record_sanitized(1);
+        }
+        self.peaks.store(peaks, Ordering::Relaxed);
+    }
+
+    // Convert peak values to dBFS
+    fn peaks_to_dbfs(peaks: PeakSamples) -> (Decibel, Decibel) {
+        (20.0 * peaks.window.log10(), 20.0 * peaks.held.log10())
+    }
+}
+
+// Internal state of a SamplePeakMeter, see serialize_state()
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SamplePeakState {
+    // Highest sample since the last read_and_reset()
+    pub window: Sample,
+
+    // Highest sample since the last reset_hold()
+    pub held: Sample,
+}
+
+impl Default for SamplePeakMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Meter for SamplePeakMeter {
+    // The sample peak does not depend on the sampling rate
+    fn new_for(_sampling_rate: u32) -> Self {
+        Self::new()
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    // Unlike read_and_reset(), this does not reset the peak meter
+    fn read(&self) -> Decibel {
+        self.read_with_hold().0
+    }
+
+    // This resets both the current and held peak values
+    fn reset(&self) {
+        self.peaks.store(PeakSamples { window: 0.0, held: 0.0 },
+                         Ordering::Relaxed);
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::SamplePeak { polarity: self.polarity }
     }
 }
 
@@ -55,6 +371,284 @@ impl UnwindSafe for SamplePeakMeter {}
 impl RefUnwindSafe for SamplePeakMeter {}
 
 
+// A peak meter with a short attack time, for less twitchy displays
+//
+// SamplePeakMeter jumps on any single outlier sample, which some users find
+// too twitchy for a display. This one follows the absolute value of samples
+// with an envelope that rises with a short attack time constant and falls back
+// with a longer release time constant, then reports the highest envelope value
+// since the last read, like quasi-peak programme meters do. A level must thus
+// be sustained for about the attack time to be fully displayed, while isolated
+// glitches barely register. Use SamplePeakMeter alongside it if you also want
+// the actual highest sample.
+//
+pub struct QuasiPeakMeter {
+    // Current envelope and its highest value since the last read
+    state: Atomic<QuasiPeakState>,
+
+    // Weights of the old envelope vs new samples
+    weights: Atomic<QuasiPeakWeights>,
+
+    // Sampling rate that the weights were computed for
+    sampling_rate: Atomic<u32>,
+
+    // Attack time constant, in seconds
+    attack_time: f32,
+
+    // Number of times non-finite samples made the state reset
+    sanitized_events: Atomic<u64>,
+}
+
+// State of QuasiPeakMeter, used both for batched integration and for
+// serialize_state()
+//
+// Packed together and aligned so that it can be updated atomically without
+// locking on 64-bit CPUs.
+//
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C, align(8))]
+pub struct QuasiPeakState {
+    // Current value of the envelope
+    pub envelope: Sample,
+
+    // Highest envelope value since the last read_and_reset()
+    pub window: Sample,
+}
+
+// Weights of QuasiPeakMeter's envelope follower
+//
+// Same packing and alignment considerations as QuasiPeakState.
+//
+#[derive(Clone, Copy)]
+#[repr(C, align(8))]
+struct QuasiPeakWeights {
+    // Weight used when samples are above the envelope
+    attack: f32,
+
+    // Weight used when samples are below the envelope
+    release: f32,
+}
+
+impl QuasiPeakMeter {
+    // Default attack time constant, in seconds
+    pub const DEFAULT_ATTACK_TIME: f32 = 0.001;
+
+    // Release time constant, in seconds
+    //
+    // This only needs to be long with respect to the period of audible
+    // signals, so that the envelope does not sag between two waveform peaks.
+    //
+    const RELEASE_TIME: f64 = 0.1;
+
+    // Set up a quasi-peak meter for a given sampling rate and attack time
+    // constant (in seconds)
+    pub fn new(sampling_rate: u32, attack_time: f32) -> Self {
+        assert!(attack_time >= 0.0, "Attack time must be positive");
+        Self {
+            state: Atomic::new(QuasiPeakState::default()),
+            weights: Atomic::new(Self::weights(sampling_rate, attack_time)),
+            sampling_rate: Atomic::new(sampling_rate),
+            attack_time,
+            sanitized_events: Atomic::new(0),
+        }
+    }
+
+    // Compute the envelope follower weights for a given sampling rate and
+    // attack time. As with the VU-meter, we go through double precision.
+    fn weights(sampling_rate: u32, attack_time: f32) -> QuasiPeakWeights {
+        let dt = 1.0 / (sampling_rate as f64);
+        QuasiPeakWeights {
+            attack: (-dt/(attack_time as f64)).exp() as f32,
+            release: (-dt/Self::RELEASE_TIME).exp() as f32,
+        }
+    }
+
+    // Update the sampling rate, please remember to call this if your audio
+    // API allows changing the sampling rate in the middle of an audio stream.
+    pub fn update_sampling_rate(&self, sampling_rate: u32) {
+        self.weights.store(Self::weights(sampling_rate, self.attack_time),
+                           Ordering::Relaxed);
+        self.sampling_rate.store(sampling_rate, Ordering::Relaxed);
+    }
+
+    // Query the sampling rate that the quasi-peak meter is configured for
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate.load(Ordering::Relaxed)
+    }
+
+    // Query the attack time constant of the quasi-peak meter, in seconds
+    pub fn attack_time(&self) -> f32 {
+        self.attack_time
+    }
+
+    // Feed new data into the quasi-peak meter
+    pub fn integrate<I, II>(&self, data: II)
+        where II: IntoIterator<Item=Sample, IntoIter=I>,
+              I: Iterator<Item=Sample> + Clone,
+    {
+        let data_iter = data.into_iter();
+        let mut old_state = self.state.load(Ordering::Relaxed);
+        loop {
+            let (new_state, sanitized) =
+                self.fold(old_state, data_iter.clone());
+            match self.state.compare_exchange(old_state,
+                                              new_state,
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
+                Ok(_) => {
+                    if sanitized { self.record_sanitized(); }
+                    return;
+                }
+                Err(new_old_state) => old_state = new_old_state,
+            }
+        }
+    }
+
+    // Start accumulating data locally, without touching the shared state
+    //
+    // This works like VUMeter::begin_batch(), and has the same single-thread
+    // restriction.
+    //
+    pub fn begin_batch(&self) -> QuasiPeakState {
+        QuasiPeakState { window: 0.0, ..self.state.load(Ordering::Relaxed) }
+    }
+
+    // Feed new data into a local quasi-peak accumulator
+    //
+    // If non-finite samples made the accumulator non-finite, it is reset, as
+    // the envelope would otherwise never recover from it.
+    //
+    pub fn integrate_batch(&self,
+                           state: &mut QuasiPeakState,
+                           data: impl IntoIterator<Item=Sample>) {
+        let (new_state, sanitized) = self.fold(*state, data);
+        *state = new_state;
+        if sanitized { self.record_sanitized(); }
+    }
+
+    // Run the envelope follower over some samples, starting from a known
+    // state, and tell if non-finite samples made the state reset
+    fn fold(&self,
+            mut state: QuasiPeakState,
+            data: impl IntoIterator<Item=Sample>) -> (QuasiPeakState, bool) {
+        let weights = self.weights.load(Ordering::Relaxed);
+        for spl in data {
+            let abs = spl.abs();
+            let weight = if abs > state.envelope {
+                weights.attack
+            } else {
+                weights.release
+            };
+            state.envelope = abs + (state.envelope - abs) * weight;
+            state.window = state.window.max(state.envelope);
+        }
+        if state.envelope.is_finite() && state.window.is_finite() {
+            (state, false)
+        } else {
+            (QuasiPeakState::default(), true)
+        }
+    }
+
+    // Publish the result of local quasi-peak accumulation
+    pub fn end_batch(&self, state: QuasiPeakState) {
+        // NOTE: The window peak may have been reset in the meantime, in which
+        //       case only the local window peak should be kept.
+        self.update_state(|old_state| QuasiPeakState {
+            envelope: state.envelope,
+            window: old_state.window.max(state.window),
+        });
+    }
+
+    // Query the highest value of the envelope since the last read, in dBFS,
+    // and reset it (the envelope itself is left alone)
+    pub fn read_and_reset(&self) -> Decibel {
+        let old_state =
+            self.update_state(|state| QuasiPeakState { window: 0.0, ..state });
+        20.0 * old_state.window.log10()
+    }
+
+    // Count one more batch of samples that made the state reset
+    fn record_sanitized(&self) {
+        self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Query how many times non-finite samples made the state reset, which is
+    // a sign of a misbehaving audio source
+    pub fn sanitized_events(&self) -> u64 {
+        self.sanitized_events.load(Ordering::Relaxed)
+    }
+
+    // Save the internal state of the quasi-peak meter, e.g. to resume a
+    // measurement after a restart with restore_state()
+    //
+    // As with the VU-meter, configuration is not included.
+    //
+    pub fn serialize_state(&self) -> QuasiPeakState {
+        self.state.load(Ordering::Relaxed)
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // A non-finite state is reset, as the envelope would never recover.
+    //
+    pub fn restore_state(&self, state: QuasiPeakState) {
+        let mut state = state;
+        if !(state.envelope.is_finite() && state.window.is_finite()) {
+            state = QuasiPeakState::default();
+            self.record_sanitized();
+        }
+        self.state.store(state, Ordering::Relaxed);
+    }
+
+    // Atomically update the state, return the previous one
+    fn update_state<F>(&self, update: F) -> QuasiPeakState
+        where F: Fn(QuasiPeakState) -> QuasiPeakState
+    {
+        let mut old_state = self.state.load(Ordering::Relaxed);
+        loop {
+            match self.state.compare_exchange(old_state,
+                                              update(old_state),
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
+                Ok(_) => return old_state,
+                Err(new_old_state) => old_state = new_old_state,
+            }
+        }
+    }
+}
+
+impl Meter for QuasiPeakMeter {
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate, Self::DEFAULT_ATTACK_TIME)
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    // Unlike read_and_reset(), this does not reset the window peak
+    fn read(&self) -> Decibel {
+        20.0 * self.state.load(Ordering::Relaxed).window.log10()
+    }
+
+    fn reset(&self) {
+        self.state.store(QuasiPeakState::default(), Ordering::Relaxed);
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::QuasiPeak {
+            sampling_rate: self.sampling_rate(),
+            attack_time: self.attack_time,
+        }
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl UnwindSafe for QuasiPeakMeter {}
+impl RefUnwindSafe for QuasiPeakMeter {}
+
+
 // A basic VU-meter-ish thing
 //
 // It does not actually measure VU, being dBFS-based, but that doesn't actually
@@ -69,16 +663,125 @@ pub struct VUMeter {
     // Current VU value, as an FP sample
     vu_sample: Atomic<Sample>,
 
+    // Peak hold overlay, i.e. recent highest VU value and remaining hold time
+    held: Atomic<HeldVU>,
+
+    // Hold time and release rate of the peak hold overlay
+    peak_hold: Atomic<PeakHold>,
+
     // Weight of old VU vs new samples
     vu_weight: Atomic<f32>,
+
+    // Sampling rate that the VU weight was computed for
+    sampling_rate: Atomic<u32>,
+
+    // Correction applied to the absolute value of samples
+    correction: AmplitudeCorrection,
+
+    // Level which reads as 0 VU, in dBFS
+    reference: Atomic<Decibel>,
+
+    // Number of times out-of-range samples had to be clamped or ignored
+    sanitized_events: Atomic<u64>,
+}
+
+// Correction applied by the VU-meter to the absolute value of samples
+//
+// The VU-meter averages the absolute value of samples, which underestimates the
+// level of the signal by an amount that depends on the signal's waveform. This
+// tells which kind of level we want to estimate, and for which waveform.
+//
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AmplitudeCorrection {
+    // Estimate the amplitude of a sine wave (this is the default)
+    #[default]
+    SineAmplitude,
+
+    // Estimate the RMS level of a sine wave, which is 3 dB below its amplitude.
+    // This reads closer to true RMS for program material, although it is only
+    // exact for sines.
+    SineRms,
+
+    // Report the average absolute value of samples without any correction
+    None,
+}
+
+impl AmplitudeCorrection {
+    // Factor by which the absolute value of samples should be multiplied
+    fn factor(self) -> f32 {
+        use std::f32::consts::{FRAC_1_SQRT_2, PI};
+        match self {
+            // The average of |sin| is 2/pi, so that's what we must undo...
+            AmplitudeCorrection::SineAmplitude => PI / 2.0,
+            // ...and then the RMS of a sine is its amplitude divided by sqrt(2)
+            AmplitudeCorrection::SineRms => PI / 2.0 * FRAC_1_SQRT_2,
+            AmplitudeCorrection::None => 1.0,
+        }
+    }
+}
+
+// Ballistics of the peak marker which VUMeter overlays on its readings
+//
+// The marker jumps to every new highest VU value, holds it for some time, then
+// falls back at a constant rate until it meets the VU value again. Same
+// packing and alignment considerations as HeldVU.
+//
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C, align(8))]
+pub struct PeakHold {
+    // Time during which a new peak is held, in seconds
+    pub hold_time: f32,
+
+    // Rate at which the marker falls back after the hold time, in dB/s
+    pub release_rate: f32,
+}
+
+impl Default for PeakHold {
+    // By default, peaks are held for 1.5s, then released like a BBC PPM
+    fn default() -> Self {
+        Self {
+            hold_time: 1.5,
+            release_rate: DecayRate::BbcPpm.db_per_second(),
+        }
+    }
+}
+
+// Current state of VUMeter's peak hold overlay
+//
+// Packed together so that it can be updated atomically, and aligned so that
+// this can be done without locking on 64-bit CPUs.
+//
+#[derive(Clone, Copy, Default)]
+#[repr(C, align(8))]
+struct HeldVU {
+    // Held VU value, as an FP sample
+    level: Sample,
+
+    // Number of samples during which this value remains held
+    hold_left: u32,
 }
 
 impl VUMeter {
-    // Set up a VU-meter for a given sampling rate
-    pub fn new(sampling_rate: u32) -> Self {
+    // Highest sample magnitude that the VU-meter accepts (+60 dBFS)
+    //
+    // Floating-point audio can legitimately go above 0 dBFS, but huge or
+    // non-finite samples would push the VU value to infinity or NaN, and it
+    // would never recover from that. So we clamp samples to this magnitude,
+    // and treat NaN samples as silence.
+    //
+    const MAX_AMPLITUDE: Sample = 1000.0;
+
+    // Set up a VU-meter for a given sampling rate and amplitude correction
+    pub fn new(sampling_rate: u32, correction: AmplitudeCorrection) -> Self {
         Self {
             vu_sample: Atomic::new(0.0),
+            held: Atomic::new(HeldVU::default()),
+            peak_hold: Atomic::new(PeakHold::default()),
             vu_weight: Atomic::new(Self::vu_weight(sampling_rate)),
+            sampling_rate: Atomic::new(sampling_rate),
+            correction,
+            reference: Atomic::new(0.0),
+            sanitized_events: Atomic::new(0),
         }
     }
 
@@ -94,7 +797,8 @@ impl VUMeter {
         //     VU_new(300ms) = 0.99 * |a|
         //
         // The average of the absolute value of a sine is its amplitude times
-        // 2/pi, so corr = pi/2. And ln(0.01) ~ 4.6 so we want
+        // 2/pi, so corr = pi/2 (other choices of corr are possible, see
+        // AmplitudeCorrection). And ln(0.01) ~ 4.6 so we want
         // 300ms = 4.6 * tau => tau = 300ms / 4.6.
         //
         // But wait. dt between two samples is fully determined by the sampling
@@ -105,52 +809,1967 @@ impl VUMeter {
         //
         // That's awesome! Let's do it then.
         //
-        const RISE_TIME: f32 = 0.3;
-        const RISE_PRECISION: f32 = 0.01;
+        // One catch is that at high sampling rates, dt/tau gets very small and
+        // the weight gets very close to 1, so single-precision intermediate
+        // computations would lose much of the weight's precision (which is in
+        // its distance to 1). We avoid this by computing the weight in double
+        // precision, so that only the final rounding to f32 remains. At 192kHz,
+        // that's a relative error below 1e-3 on 1 - weight, i.e. on tau.
+        //
+        const RISE_TIME: f64 = 0.3;
+        const RISE_PRECISION: f64 = 0.01;
         let tau = -RISE_TIME / RISE_PRECISION.ln();
-        let dt = 1.0 / (sampling_rate as f32);
-        (-dt/tau).exp()
+        let dt = 1.0 / (sampling_rate as f64);
+        (-dt/tau).exp() as f32
     }
 
     // Update the sampling rate, please remember to call this if your audio
     // API allows changing the sampling rate in the middle of an audio stream.
     pub fn update_sampling_rate(&self, sampling_rate: u32) {
         self.vu_weight.store(Self::vu_weight(sampling_rate), Ordering::Relaxed);
+        self.sampling_rate.store(sampling_rate, Ordering::Relaxed);
+    }
+
+    // Query the sampling rate that the VU-meter is configured for
+    //
+    // The VU-meter's ballistics are silently wrong if it is fed audio with a
+    // different sampling rate, so audio backends should check this.
+    //
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate.load(Ordering::Relaxed)
+    }
+
+    // Query the amplitude correction of the VU-meter
+    pub fn amplitude_correction(&self) -> AmplitudeCorrection {
+        self.correction
+    }
+
+    // Set the level which should read as 0 VU, in dBFS
+    //
+    // Analog VU-meters are calibrated so that 0 VU matches some nominal
+    // operating level, which digital setups usually align with -18 or -20
+    // dBFS. By default, the reference is 0 dBFS, so the meter reads in VUFS.
+    //
+    pub fn set_reference(&self, reference: Decibel) {
+        self.reference.store(reference, Ordering::Relaxed);
+    }
+
+    // Query the level which reads as 0 VU, in dBFS
+    pub fn reference(&self) -> Decibel {
+        self.reference.load(Ordering::Relaxed)
+    }
+
+    // Set the hold time and release rate of the peak hold overlay
+    //
+    // This takes effect on the next samples, a peak which is currently held
+    // keeps the hold time that it started with.
+    //
+    pub fn set_peak_hold(&self, peak_hold: PeakHold) {
+        assert!(peak_hold.hold_time >= 0.0, "Hold time must be positive");
+        assert!(peak_hold.release_rate >= 0.0, "Release rate must be positive");
+        self.peak_hold.store(peak_hold, Ordering::Relaxed);
+    }
+
+    // Query the hold time and release rate of the peak hold overlay
+    pub fn peak_hold(&self) -> PeakHold {
+        self.peak_hold.load(Ordering::Relaxed)
     }
 
     // Feed samples into the API
+    //
+    // Concurrent calls keep the VU value exact, but the peak hold overlay is
+    // only updated by the last one to complete, so it may miss some peaks.
+    //
     pub fn integrate<I, II>(&self, data: II)
         where II: IntoIterator<Item=Sample, IntoIter=I>,
               I: Iterator<Item=Sample> + Clone,
     {
-        const AMPLITUDE_CORRECTION: f32 = std::f32::consts::PI / 2.0;
         let data_iter = data.into_iter();
         let mut old_vu = self.vu_sample.load(Ordering::Relaxed);
         loop {
-            let vu_weight =
-                self.vu_weight.load(Ordering::Relaxed);
-            let new_vu =
-                data_iter.clone()
-                         .map(|spl| spl.abs() * AMPLITUDE_CORRECTION)
-                         .fold(old_vu, |vu, spl| {
-                             spl + (vu - spl) * vu_weight
-                         });
+            let old_state = self.state(old_vu);
+            let (new_state, sanitized) = self.fold(old_state,
+                                                   data_iter.clone());
             match self.vu_sample.compare_exchange(old_vu,
-                                                  new_vu,
+                                                  new_state.vu_sample,
                                                   Ordering::Relaxed,
                                                   Ordering::Relaxed) {
-                Ok(_) => return,
+                Ok(_) => {
+                    self.store_held(new_state);
+                    if sanitized { self.record_sanitized(); }
+                    return;
+                }
                 Err(new_old_vu) => old_vu = new_old_vu,
             }
         }
     }
 
-    // Read the current VU-meter value in VUFS
+    // Start accumulating samples locally, without touching the shared VU value
+    //
+    // This is meant for callers which want to integrate several buffers of
+    // audio data before publishing the result, in order to reduce atomic
+    // traffic. See integrate_batch() and end_batch() for the rest.
+    //
+    // WARNING: Unlike integrate(), batched integration assumes that there is
+    //          only one thread feeding samples into the VU-meter. Resets and
+    //          state restorations made by other threads while a batch is in
+    //          progress are overwritten when the batch is published.
+    //
+    pub fn begin_batch(&self) -> VUState {
+        self.state(self.vu_sample.load(Ordering::Relaxed))
+    }
+
+    // Feed samples into a local VU accumulator
+    pub fn integrate_batch(&self,
+                           state: &mut VUState,
+                           data: impl IntoIterator<Item=Sample>) {
+        let (new_state, sanitized) = self.fold(*state, data);
+        *state = new_state;
+        if sanitized { self.record_sanitized(); }
+    }
+
+    // Publish the result of local VU accumulation
+    pub fn end_batch(&self, state: VUState) {
+        self.vu_sample.store(state.vu_sample, Ordering::Relaxed);
+        self.store_held(state);
+    }
+
+    // Combine a VU value with the current peak hold overlay
+    fn state(&self, vu_sample: Sample) -> VUState {
+        let held = self.held.load(Ordering::Relaxed);
+        VUState { vu_sample, held: held.level, hold_left: held.hold_left }
+    }
+
+    // Publish the peak hold overlay of some VU state
+    fn store_held(&self, state: VUState) {
+        self.held.store(HeldVU { level: state.held,
+                                 hold_left: state.hold_left },
+                        Ordering::Relaxed);
+    }
+
+    // Run the VU recursion and the peak hold overlay over some samples,
+    // starting from a known state, and tell if some out-of-range samples had
+    // to be clamped or ignored
+    fn fold(&self,
+            state: VUState,
+            data: impl IntoIterator<Item=Sample>) -> (VUState, bool) {
+        let vu_weight = self.vu_weight.load(Ordering::Relaxed);
+        let correction = self.correction.factor();
+        let (hold_samples, release) = self.peak_hold_params();
+        let mut sanitized = false;
+        let state = data.into_iter()
+                        .map(|spl| {
+                            let abs = spl.abs();
+                            if abs <= Self::MAX_AMPLITUDE {
+                                abs
+                            } else if abs.is_nan() {
+                                sanitized = true;
+                                0.0
+                            } else {
+                                sanitized = true;
+                                Self::MAX_AMPLITUDE
+                            }
+                        })
+                        .map(|spl| spl * correction)
+                        .fold(state, |state, spl| {
+                            let vu = spl + (state.vu_sample - spl) * vu_weight;
+                            let held = if state.hold_left > 0 {
+                                state.held
+                            } else {
+                                state.held * release
+                            };
+                            if vu >= held {
+                                VUState { vu_sample: vu,
+                                          held: vu,
+                                          hold_left: hold_samples }
+                            } else {
+                                VUState { vu_sample: vu,
+                                          held,
+                                          hold_left: state.hold_left
+                                                          .saturating_sub(1) }
+                            }
+                        });
+        (state, sanitized)
+    }
+
+    // Hold time of the peak hold overlay in samples, and factor by which the
+    // held value is multiplied on every sample after that
+    fn peak_hold_params(&self) -> (u32, Sample) {
+        let peak_hold = self.peak_hold();
+        let sampling_rate = self.sampling_rate() as f64;
+        let hold_samples = (peak_hold.hold_time as f64 * sampling_rate) as u32;
+        let release_db = -peak_hold.release_rate as f64 / sampling_rate;
+        (hold_samples, 10.0f64.powf(release_db / 20.0) as Sample)
+    }
+
+    // Count one more batch of samples that had to be clamped or ignored
+    fn record_sanitized(&self) {
+        self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Query how many times out-of-range samples had to be clamped (huge or
+    // infinite samples) or ignored (NaN samples), which is a sign of a
+    // misbehaving audio source
+    pub fn sanitized_events(&self) -> u64 {
+        self.sanitized_events.load(Ordering::Relaxed)
+    }
+
+    // Save the internal state of the VU-meter, e.g. to resume a measurement
+    // after a restart with restore_state()
+    //
+    // Configuration (sampling rate, correction, reference) is not included,
+    // the state should be restored into an identically configured VU-meter.
+    //
+    pub fn serialize_state(&self) -> VUState {
+        self.begin_batch()
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // A non-finite VU value or held peak is reset, as it would never recover.
+    //
+    pub fn restore_state(&self, mut state: VUState) {
+        if !(state.vu_sample.is_finite() && state.held.is_finite()) {
+            state = VUState::default();
+            self.record_sanitized();
+        }
+        self.end_batch(state);
+    }
+
+    // Read the current VU-meter value in VU, relative to the reference level
+    // (which means VUFS if the reference was left at 0 dBFS)
     pub fn read(&self) -> Decibel {
-        20.0 * self.vu_sample.load(Ordering::Relaxed).log10()
+        20.0 * self.vu_sample.load(Ordering::Relaxed).log10() - self.reference()
+    }
+
+    // Read the peak hold overlay in VU, relative to the reference level
+    //
+    // This is never below the current VU value, see PeakHold for its
+    // ballistics.
+    //
+    pub fn read_peak_hold(&self) -> Decibel {
+        let held = self.held.load(Ordering::Relaxed).level;
+        20.0 * held.log10() - self.reference()
     }
 }
 
-// FIXME: Atomic crate should do this for me
-impl UnwindSafe for VUMeter {}
-impl RefUnwindSafe for VUMeter {}
\ No newline at end of file
+// State of a VUMeter, used both for batched integration and for
+// serialize_state()
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VUState {
+    // Current VU value, as an FP sample
+    pub vu_sample: Sample,
+
+    // Value held by the peak hold overlay, as an FP sample
+    pub held: Sample,
+
+    // Number of samples during which the held value remains held
+    pub hold_left: u32,
+}
+
+impl Meter for VUMeter {
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate, AmplitudeCorrection::default())
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    fn read(&self) -> Decibel {
+        VUMeter::read(self)
+    }
+
+    fn reset(&self) {
+        self.end_batch(VUState::default());
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::VU {
+            sampling_rate: self.sampling_rate(),
+            correction: self.correction,
+            reference: self.reference(),
+            peak_hold: self.peak_hold(),
+        }
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl UnwindSafe for VUMeter {}
+impl RefUnwindSafe for VUMeter {}
+
+
+// An RMS meter with standard sound level meter time weightings
+//
+// This computes an exponential moving average of the squared signal, with the
+// time constants that IEC 61672 specifies for sound level meters. Unlike the
+// VU-meter, it measures the actual RMS level of any signal, not only sines.
+// It does not account for the frequency response of human hearing either.
+//
+// With the "Impulse" time weighting, the moving average is followed by a peak
+// detector which decays slowly, so that short bursts remain readable.
+//
+pub struct RmsMeter {
+    // Current mean square and peak detector output
+    state: Atomic<RmsState>,
+
+    // Weights of the moving average and decay of the peak detector
+    weights: Atomic<RmsWeights>,
+
+    // Sampling rate that the weights were computed for
+    sampling_rate: Atomic<u32>,
+
+    // Time weighting that the weights were computed for
+    time_weighting: TimeWeighting,
+
+    // Number of times non-finite samples made the mean square reset
+    sanitized_events: Atomic<u64>,
+}
+
+// Standard time weightings of sound level meters
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimeWeighting {
+    // "Fast" time weighting: 125ms time constant (this is the default)
+    #[default]
+    Fast,
+
+    // "Slow" time weighting: 1s time constant
+    Slow,
+
+    // "Impulse" time weighting: 35ms time constant, followed by a peak
+    // detector with a 1.5s decay time constant
+    Impulse,
+}
+
+impl TimeWeighting {
+    // Time constant of the moving average, in seconds
+    fn averaging_time(self) -> f64 {
+        match self {
+            TimeWeighting::Fast => 0.125,
+            TimeWeighting::Slow => 1.0,
+            TimeWeighting::Impulse => 0.035,
+        }
+    }
+
+    // Decay time constant of the peak detector, in seconds, if there is one
+    fn peak_decay_time(self) -> Option<f64> {
+        match self {
+            TimeWeighting::Fast | TimeWeighting::Slow => None,
+            TimeWeighting::Impulse => Some(1.5),
+        }
+    }
+}
+
+// Weights of the RMS meter's moving average and peak detector
+//
+// Same packing and alignment considerations as RmsState.
+//
+#[derive(Clone, Copy)]
+#[repr(C, align(8))]
+struct RmsWeights {
+    // Weight of the old mean square vs new squared samples
+    average: f32,
+
+    // Decay of the peak detector per sample. This is zero when there is no
+    // peak detector, so that it simply follows the mean square.
+    peak_decay: f32,
+}
+
+impl RmsMeter {
+    // Set up an RMS meter for a given sampling rate and time weighting
+    pub fn new(sampling_rate: u32, time_weighting: TimeWeighting) -> Self {
+        Self {
+            state: Atomic::new(RmsState::default()),
+            weights: Atomic::new(Self::weights(sampling_rate, time_weighting)),
+            sampling_rate: Atomic::new(sampling_rate),
+            time_weighting,
+            sanitized_events: Atomic::new(0),
+        }
+    }
+
+    // Compute the moving average weights for a given sampling rate and time
+    // weighting. As with the VU-meter, we go through double precision to
+    // avoid losing precision at high sampling rates.
+    fn weights(sampling_rate: u32,
+               time_weighting: TimeWeighting) -> RmsWeights {
+        let dt = 1.0 / (sampling_rate as f64);
+        let tau_average = time_weighting.averaging_time();
+        let peak_decay = time_weighting.peak_decay_time()
+                                       .map_or(0.0, |tau| (-dt/tau).exp());
+        RmsWeights {
+            average: (-dt/tau_average).exp() as f32,
+            peak_decay: peak_decay as f32,
+        }
+    }
+
+    // Update the sampling rate, please remember to call this if your audio
+    // API allows changing the sampling rate in the middle of an audio stream.
+    pub fn update_sampling_rate(&self, sampling_rate: u32) {
+        self.weights.store(Self::weights(sampling_rate, self.time_weighting),
+                           Ordering::Relaxed);
+        self.sampling_rate.store(sampling_rate, Ordering::Relaxed);
+    }
+
+    // Query the sampling rate that the RMS meter is configured for
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate.load(Ordering::Relaxed)
+    }
+
+    // Query the time weighting of the RMS meter
+    pub fn time_weighting(&self) -> TimeWeighting {
+        self.time_weighting
+    }
+
+    // Feed samples into the RMS meter
+    pub fn integrate<I, II>(&self, data: II)
+        where II: IntoIterator<Item=Sample, IntoIter=I>,
+              I: Iterator<Item=Sample> + Clone,
+    {
+        let data_iter = data.into_iter();
+        let mut old_state = self.state.load(Ordering::Relaxed);
+        loop {
+            let (new_state, sanitized) = self.fold(old_state,
+                                                   data_iter.clone());
+            match self.state.compare_exchange(old_state,
+                                              new_state,
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
+                Ok(_) => {
+                    if sanitized { self.record_sanitized(); }
+                    return;
+                }
+                Err(new_old_state) => old_state = new_old_state,
+            }
+        }
+    }
+
+    // Start accumulating samples locally, without touching the shared state
+    //
+    // This works like VUMeter::begin_batch(), and has the same single-thread
+    // restriction.
+    //
+    pub fn begin_batch(&self) -> RmsState {
+        self.state.load(Ordering::Relaxed)
+    }
+
+    // Feed samples into a local RMS state
+    pub fn integrate_batch(&self,
+                           state: &mut RmsState,
+                           data: impl IntoIterator<Item=Sample>) {
+        let (new_state, sanitized) = self.fold(*state, data);
+        *state = new_state;
+        if sanitized { self.record_sanitized(); }
+    }
+
+    // Publish the result of local RMS state accumulation
+    pub fn end_batch(&self, state: RmsState) {
+        self.state.store(state, Ordering::Relaxed);
+    }
+
+    // Run the moving average and peak detector over some samples, from a
+    // known state
+    //
+    // If non-finite samples made the state non-finite, it is reset, as the
+    // moving average would otherwise never recover from it, and this is
+    // reported by returning true alongside the new state.
+    //
+    fn fold(&self,
+            state: RmsState,
+            data: impl IntoIterator<Item=Sample>) -> (RmsState, bool) {
+        let weights = self.weights.load(Ordering::Relaxed);
+        let state = data.into_iter()
+                        .map(|spl| spl * spl)
+                        .fold(state, |state, spl2| {
+                            let ms = spl2 + (state.mean_square - spl2)
+                                            * weights.average;
+                            let decayed = state.level * weights.peak_decay;
+                            RmsState {
+                                mean_square: ms,
+                                level: if ms > decayed { ms } else { decayed },
+                            }
+                        });
+        if state.mean_square.is_finite() && state.level.is_finite() {
+            (state, false)
+        } else {
+            (RmsState::default(), true)
+        }
+    }
+
+    // Count one more batch of samples that made the mean square reset
+    fn record_sanitized(&self) {
+        self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Save the internal state of the RMS meter, e.g. to resume a measurement
+    // after a restart with restore_state()
+    //
+    // As with the VU-meter, configuration is not included.
+    //
+    pub fn serialize_state(&self) -> RmsState {
+        self.state.load(Ordering::Relaxed)
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // A non-finite state is reset, as it would never recover.
+    //
+    pub fn restore_state(&self, mut state: RmsState) {
+        if !(state.mean_square.is_finite() && state.level.is_finite()) {
+            state = RmsState::default();
+            self.record_sanitized();
+        }
+        self.state.store(state, Ordering::Relaxed);
+    }
+
+    // Read the current RMS level in dBFS
+    pub fn read(&self) -> Decibel {
+        10.0 * self.state.load(Ordering::Relaxed).level.log10()
+    }
+
+    // Query how many times non-finite samples made the mean square reset,
+    // which is a sign of a misbehaving audio source
+    pub fn sanitized_events(&self) -> u64 {
+        self.sanitized_events.load(Ordering::Relaxed)
+    }
+}
+
+// State of RmsMeter, used both for batched integration and for
+// serialize_state()
+//
+// Packed together and aligned so that it can be updated atomically without
+// locking on 64-bit CPUs.
+//
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C, align(8))]
+pub struct RmsState {
+    // Current mean square value
+    pub mean_square: f32,
+
+    // Output of the peak detector, which is the level that the meter reads.
+    // This is the mean square itself, except with Impulse time weighting.
+    pub level: f32,
+}
+
+impl Meter for RmsMeter {
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate, TimeWeighting::default())
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    fn read(&self) -> Decibel {
+        RmsMeter::read(self)
+    }
+
+    fn reset(&self) {
+        self.state.store(RmsState::default(), Ordering::Relaxed);
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::Rms {
+            sampling_rate: self.sampling_rate(),
+            time_weighting: self.time_weighting,
+        }
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl UnwindSafe for RmsMeter {}
+impl RefUnwindSafe for RmsMeter {}
+
+
+// A session-wide RMS level, meant as a cheap approximation of loudness
+//
+// This averages the mean square of the signal over consecutive 400ms blocks,
+// ignoring blocks below -70 dBFS so that silence does not drag the result
+// down, and reports the average over the whole session (or since the last
+// reset). The block length and absolute gate are borrowed from ITU-R BS.1770,
+// but there is no K-weighting and no relative gate, so this is NOT a LUFS
+// measurement. It will roughly agree with one on steady tones in the midrange,
+// and diverge on spectrally complex material.
+//
+// WARNING: This meter assumes that there is only one thread feeding samples
+//          into it, like the batched integration of other meters. Resetting
+//          or restoring its state also counts as feeding it, so this must be
+//          done by the thread that feeds it, or while nothing is feeding it.
+//
+pub struct IntegratedRmsMeter {
+    // Block which is currently being accumulated
+    block: Atomic<RmsBlock>,
+
+    // Average of the blocks which made it through the gate
+    integrated: Atomic<GatedAverage>,
+
+    // Length of a block, in samples
+    block_len: Atomic<u32>,
+
+    // Sampling rate that the block length was computed for
+    sampling_rate: Atomic<u32>,
+
+    // Number of blocks which had to be discarded for being non-finite
+    sanitized_events: Atomic<u64>,
+
+    // Level which read_relative() reads as 0 dB, in dBFS
+    reference: Atomic<Decibel>,
+}
+
+// A block of audio data being accumulated by IntegratedRmsMeter
+//
+// Packed together and aligned so that it can be read and written atomically
+// without locking on 64-bit CPUs.
+//
+#[derive(Clone, Copy, Default)]
+#[repr(C, align(8))]
+struct RmsBlock {
+    // Sum of squared samples in this block
+    sum_squares: f32,
+
+    // Number of samples in this block
+    len: u32,
+}
+
+// The average of gated blocks, tracked by IntegratedRmsMeter
+//
+// Same packing and alignment considerations as RmsBlock.
+//
+#[derive(Clone, Copy, Default)]
+#[repr(C, align(8))]
+struct GatedAverage {
+    // Mean square of the signal, averaged over all blocks above the gate
+    mean_square: f32,
+
+    // Number of blocks which went into the average
+    blocks: u32,
+}
+
+impl IntegratedRmsMeter {
+    // Duration of a block, in seconds
+    const BLOCK_DURATION: f64 = 0.4;
+
+    // Mean square below which a block is considered silent (-70 dBFS)
+    const SILENCE_GATE: f32 = 1e-7;
+
+    // Set up an integrated RMS meter for a given sampling rate
+    pub fn new(sampling_rate: u32) -> Self {
+        Self {
+            block: Atomic::new(RmsBlock { sum_squares: 0.0, len: 0 }),
+            integrated: Atomic::new(GatedAverage { mean_square: 0.0,
+                                                   blocks: 0 }),
+            block_len: Atomic::new(Self::block_len(sampling_rate)),
+            sampling_rate: Atomic::new(sampling_rate),
+            sanitized_events: Atomic::new(0),
+            reference: Atomic::new(0.0),
+        }
+    }
+
+    // Compute the block length for a given sampling rate
+    fn block_len(sampling_rate: u32) -> u32 {
+        ((sampling_rate as f64) * Self::BLOCK_DURATION).round().max(1.0) as u32
+    }
+
+    // Update the sampling rate, please remember to call this if your audio
+    // API allows changing the sampling rate in the middle of an audio stream.
+    //
+    // The block being accumulated is kept, it will just be shorter or longer.
+    //
+    pub fn update_sampling_rate(&self, sampling_rate: u32) {
+        self.block_len.store(Self::block_len(sampling_rate),
+                             Ordering::Relaxed);
+        self.sampling_rate.store(sampling_rate, Ordering::Relaxed);
+    }
+
+    // Query the sampling rate that the integrated RMS meter is configured for
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate.load(Ordering::Relaxed)
+    }
+
+    // Feed samples into the integrated RMS meter
+    //
+    // Unlike with the other meters, concurrent calls to this function can lose
+    // updates, see the top-level documentation of this meter.
+    //
+    pub fn integrate(&self, data: impl IntoIterator<Item=Sample>) {
+        let mut state = self.begin_batch();
+        self.integrate_batch(&mut state, data);
+        self.end_batch(state);
+    }
+
+    // Start accumulating samples locally, without touching the shared state
+    //
+    // This works like VUMeter::begin_batch(), and has the same single-thread
+    // restriction (which applies to integrate() as well here).
+    //
+    pub fn begin_batch(&self) -> IntegratedRmsBatch {
+        IntegratedRmsBatch {
+            block: self.block.load(Ordering::Relaxed),
+            integrated: self.integrated.load(Ordering::Relaxed),
+        }
+    }
+
+    // Feed samples into a local integrated RMS accumulator
+    //
+    // Blocks which non-finite samples made non-finite are discarded, as they
+    // would otherwise make the average non-finite until the next reset.
+    //
+    pub fn integrate_batch(&self,
+                           state: &mut IntegratedRmsBatch,
+                           data: impl IntoIterator<Item=Sample>) {
+        let block_len = self.block_len.load(Ordering::Relaxed);
+        let IntegratedRmsBatch { block, integrated } = state;
+        for spl in data {
+            block.sum_squares += spl * spl;
+            block.len += 1;
+            if block.len >= block_len {
+                let block_ms = block.sum_squares / (block.len as f32);
+                if !block_ms.is_finite() {
+                    self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+                } else if block_ms >= Self::SILENCE_GATE {
+                    integrated.blocks += 1;
+                    integrated.mean_square +=
+                        (block_ms - integrated.mean_square)
+                            / (integrated.blocks as f32);
+                }
+                *block = RmsBlock { sum_squares: 0.0, len: 0 };
+            }
+        }
+    }
+
+    // Publish the result of local integrated RMS accumulation
+    pub fn end_batch(&self, state: IntegratedRmsBatch) {
+        self.block.store(state.block, Ordering::Relaxed);
+        self.integrated.store(state.integrated, Ordering::Relaxed);
+    }
+
+    // Save the internal state of the integrated RMS meter, e.g. to resume a
+    // measurement session after a restart with restore_state()
+    //
+    // As with the VU-meter, configuration is not included. Like integrate(),
+    // this assumes that no other thread is feeding samples concurrently.
+    //
+    pub fn serialize_state(&self) -> IntegratedRmsState {
+        let block = self.block.load(Ordering::Relaxed);
+        let integrated = self.integrated.load(Ordering::Relaxed);
+        IntegratedRmsState {
+            block_sum_squares: block.sum_squares,
+            block_len: block.len,
+            mean_square: integrated.mean_square,
+            blocks: integrated.blocks,
+        }
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // Non-finite parts of the state are reset, as they would never recover.
+    // Like integrate(), this must only be called by the thread which feeds
+    // samples into the meter, if any.
+    //
+    pub fn restore_state(&self, state: IntegratedRmsState) {
+        let mut batch = IntegratedRmsBatch {
+            block: RmsBlock { sum_squares: state.block_sum_squares,
+                              len: state.block_len },
+            integrated: GatedAverage { mean_square: state.mean_square,
+                                       blocks: state.blocks },
+        };
+        if !batch.block.sum_squares.is_finite() {
+            batch.block = RmsBlock { sum_squares: 0.0, len: 0 };
+            self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+        }
+        if !batch.integrated.mean_square.is_finite() {
+            batch.integrated = GatedAverage { mean_square: 0.0, blocks: 0 };
+            self.sanitized_events.fetch_add(1, Ordering::Relaxed);
+        }
+        self.end_batch(batch);
+    }
+
+    // Read the integrated RMS level in dBFS
+    //
+    // This is -inf until a first non-silent block has been accumulated.
+    //
+    pub fn read(&self) -> Decibel {
+        10.0 * self.integrated.load(Ordering::Relaxed).mean_square.log10()
+    }
+
+    // Query how many blocks had to be discarded because non-finite samples
+    // made them non-finite, which is a sign of a misbehaving audio source
+    pub fn sanitized_events(&self) -> u64 {
+        self.sanitized_events.load(Ordering::Relaxed)
+    }
+
+    // Set the level which read_relative() should read as 0 dB, in dBFS
+    //
+    // This is meant for dialogue-anchored workflows, where program loudness
+    // is judged relative to the level of dialogue rather than to full scale.
+    // By default, the reference is 0 dBFS. It is kept across resets, so that
+    // it can be captured in one session and used in the following ones.
+    //
+    pub fn set_reference(&self, reference: Decibel) {
+        self.reference.store(reference, Ordering::Relaxed);
+    }
+
+    // Query the level which read_relative() reads as 0 dB, in dBFS
+    pub fn reference(&self) -> Decibel {
+        self.reference.load(Ordering::Relaxed)
+    }
+
+    // Use the current integrated level as the reference of read_relative()
+    //
+    // For example, measure a dialogue-only passage, capture its level, then
+    // reset the meter and measure the rest of the program relative to it.
+    // Returns the captured level in dBFS, or None (leaving the reference
+    // alone) if nothing was measured yet.
+    //
+    pub fn capture_reference(&self) -> Option<Decibel> {
+        let level = self.read();
+        if !level.is_finite() { return None; }
+        self.set_reference(level);
+        Some(level)
+    }
+
+    // Read the integrated RMS level in dB relative to the reference level
+    pub fn read_relative(&self) -> Decibel {
+        self.read() - self.reference()
+    }
+}
+
+// Local state of a batched IntegratedRmsMeter integration
+#[derive(Clone, Copy, Default)]
+pub struct IntegratedRmsBatch {
+    block: RmsBlock,
+    integrated: GatedAverage,
+}
+
+// Internal state of an IntegratedRmsMeter, see serialize_state()
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegratedRmsState {
+    // Sum of squared samples in the block being accumulated
+    pub block_sum_squares: f32,
+
+    // Number of samples in the block being accumulated
+    pub block_len: u32,
+
+    // Mean square of the signal, averaged over all blocks above the gate
+    pub mean_square: f32,
+
+    // Number of blocks which went into the average
+    pub blocks: u32,
+}
+
+impl Meter for IntegratedRmsMeter {
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate)
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    fn read(&self) -> Decibel {
+        IntegratedRmsMeter::read(self)
+    }
+
+    // This starts a new session
+    fn reset(&self) {
+        self.block.store(RmsBlock { sum_squares: 0.0, len: 0 },
+                         Ordering::Relaxed);
+        self.integrated.store(GatedAverage { mean_square: 0.0, blocks: 0 },
+                              Ordering::Relaxed);
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::IntegratedRms {
+            sampling_rate: self.sampling_rate(),
+            reference: self.reference(),
+        }
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl UnwindSafe for IntegratedRmsMeter {}
+impl RefUnwindSafe for IntegratedRmsMeter {}
+
+// A zero-crossing rate meter, meant as a cheap proxy for spectral content
+//
+// This counts how often the signal changes sign, which is a classic ingredient
+// of voice activity detection: noise-like signals cross zero much more often
+// than voiced speech or music. Samples which are exactly zero (of either sign)
+// are considered positive, so a signal must go strictly below zero and back to
+// count as crossing. The count is averaged over time with an exponential moving
+// average, like the VU-meter does for levels.
+//
+pub struct ZeroCrossingMeter {
+    // Current average crossing rate and sign of the last sample
+    state: Atomic<ZeroCrossingState>,
+
+    // Weight of the old average vs new samples
+    weight: Atomic<f32>,
+
+    // Sampling rate that the weight was computed for
+    sampling_rate: Atomic<u32>,
+}
+
+// State of ZeroCrossingMeter's moving average
+//
+// Packed together and aligned so that it can be updated atomically without
+// locking on 64-bit CPUs. The sign is stored as an f32 so that this struct has
+// no padding bytes, which would get in the way of compare-exchange.
+//
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C, align(8))]
+pub struct ZeroCrossingState {
+    // Average number of zero crossings per sample
+    pub crossings_per_sample: f32,
+
+    // Sign of the last sample, either 1.0 or -1.0
+    pub last_sign: f32,
+}
+
+impl ZeroCrossingMeter {
+    // Time constant of the moving average, in seconds
+    const TIME_CONSTANT: f64 = 0.3;
+
+    // Set up a zero-crossing rate meter for a given sampling rate
+    pub fn new(sampling_rate: u32) -> Self {
+        Self {
+            state: Atomic::new(ZeroCrossingState::default()),
+            weight: Atomic::new(Self::weight(sampling_rate)),
+            sampling_rate: Atomic::new(sampling_rate),
+        }
+    }
+
+    // Compute the moving average weight for a given sampling rate
+    fn weight(sampling_rate: u32) -> f32 {
+        let dt = 1.0 / (sampling_rate as f64);
+        (-dt/Self::TIME_CONSTANT).exp() as f32
+    }
+
+    // Update the sampling rate, please remember to call this if your audio
+    // API allows changing the sampling rate in the middle of an audio stream.
+    pub fn update_sampling_rate(&self, sampling_rate: u32) {
+        self.weight.store(Self::weight(sampling_rate), Ordering::Relaxed);
+        self.sampling_rate.store(sampling_rate, Ordering::Relaxed);
+    }
+
+    // Query the sampling rate that the meter is configured for
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate.load(Ordering::Relaxed)
+    }
+
+    // Feed samples into the zero-crossing rate meter
+    pub fn integrate<I, II>(&self, data: II)
+        where II: IntoIterator<Item=Sample, IntoIter=I>,
+              I: Iterator<Item=Sample> + Clone,
+    {
+        let data_iter = data.into_iter();
+        let mut old_state = self.state.load(Ordering::Relaxed);
+        loop {
+            let new_state = self.fold(old_state, data_iter.clone());
+            match self.state.compare_exchange(old_state,
+                                              new_state,
+                                              Ordering::Relaxed,
+                                              Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(new_old_state) => old_state = new_old_state,
+            }
+        }
+    }
+
+    // Start accumulating samples locally, without touching the shared state
+    //
+    // This works like VUMeter::begin_batch(), and has the same single-thread
+    // restriction.
+    //
+    pub fn begin_batch(&self) -> ZeroCrossingState {
+        self.state.load(Ordering::Relaxed)
+    }
+
+    // Feed samples into a local zero-crossing rate accumulator
+    pub fn integrate_batch(&self,
+                           state: &mut ZeroCrossingState,
+                           data: impl IntoIterator<Item=Sample>) {
+        *state = self.fold(*state, data);
+    }
+
+    // Publish the result of local zero-crossing rate accumulation
+    pub fn end_batch(&self, state: ZeroCrossingState) {
+        self.state.store(state, Ordering::Relaxed);
+    }
+
+    // Run the moving average over some samples, from a known state
+    fn fold(&self,
+            state: ZeroCrossingState,
+            data: impl IntoIterator<Item=Sample>) -> ZeroCrossingState {
+        let weight = self.weight.load(Ordering::Relaxed);
+        data.into_iter().fold(state, |state, spl| {
+            let sign = if spl >= 0.0 { 1.0 } else { -1.0 };
+            let crossing = if sign != state.last_sign { 1.0 } else { 0.0 };
+            ZeroCrossingState {
+                crossings_per_sample:
+                    crossing
+                    + (state.crossings_per_sample - crossing) * weight,
+                last_sign: sign,
+            }
+        })
+    }
+
+    // Read the current zero-crossing rate, in crossings per second
+    pub fn rate_hz(&self) -> f32 {
+        let state = self.state.load(Ordering::Relaxed);
+        state.crossings_per_sample * (self.sampling_rate() as f32)
+    }
+
+    // Save the internal state of the zero-crossing rate meter, e.g. to resume
+    // a measurement after a restart with restore_state()
+    //
+    // As with the VU-meter, configuration is not included.
+    //
+    pub fn serialize_state(&self) -> ZeroCrossingState {
+        self.state.load(Ordering::Relaxed)
+    }
+
+    // Restore an internal state previously saved with serialize_state()
+    //
+    // Invalid states (with a crossing rate outside of [0, 1] crossings per
+    // sample, or a sign other than 1.0 and -1.0) are reset.
+    //
+    pub fn restore_state(&self, state: ZeroCrossingState) {
+        let valid = (0.0..=1.0).contains(&state.crossings_per_sample)
+                    && (state.last_sign == 1.0 || state.last_sign == -1.0);
+        let state = if valid { state } else { ZeroCrossingState::default() };
+        self.state.store(state, Ordering::Relaxed);
+    }
+}
+
+impl Default for ZeroCrossingState {
+    // Start with no crossings, and the sign of a zero sample
+    fn default() -> Self {
+        Self { crossings_per_sample: 0.0, last_sign: 1.0 }
+    }
+}
+
+impl Meter for ZeroCrossingMeter {
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate)
+    }
+
+    fn integrate_slice(&self, data: &[Sample]) {
+        self.integrate(data.iter().copied());
+    }
+
+    // Read the zero-crossing rate, which is in crossings per second, not dB
+    fn read(&self) -> Decibel {
+        self.rate_hz()
+    }
+
+    fn reset(&self) {
+        self.state.store(ZeroCrossingState::default(), Ordering::Relaxed);
+    }
+
+    fn config(&self) -> MeterConfig {
+        MeterConfig::ZeroCrossing { sampling_rate: self.sampling_rate() }
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl UnwindSafe for ZeroCrossingMeter {}
+impl RefUnwindSafe for ZeroCrossingMeter {}
+
+
+// A wrapper which feeds only every Nth sample into a meter, to save CPU time
+//
+// On severely constrained hardware, metering every sample of every input may
+// be too expensive. This trades accuracy for CPU time by decimating the input
+// of the inner meter, without any lowpass filtering, which is configured for
+// the correspondingly reduced sampling rate.
+//
+// WARNING: This is a coarse approximation, which must only be used when the
+// full-rate meter is really unaffordable. Peak meters suffer most, since the
+// highest sample is likely to be skipped: for a sine wave at frequency f, the
+// reading of a sample peak meter can be off by up to
+// 20 * log10(cos(pi * f * factor / sampling_rate)) dB. This is already -3 dB
+// for a 1 kHz sine decimated by 12 at 48 kHz, and the error grows without
+// bound as the factor approaches sampling_rate / (2 * f), where the inner
+// meter may only see the sine's zero crossings. The averaging meters (VU,
+// RMS...) fare better on broadband program material, but signal components
+// above the reduced Nyquist frequency alias down.
+//
+pub struct Decimated<M: Meter> {
+    // Meter which receives the decimated samples
+    inner: M,
+
+    // Only one sample out of this many is fed into the inner meter
+    factor: usize,
+
+    // Index of the next sample to be fed into the inner meter, relative to the
+    // beginning of the next slice of audio data, or BUSY while a thread is
+    // feeding the meter
+    offset: Atomic<usize>,
+}
+
+// Value of Decimated::offset while a thread is feeding the meter
+const BUSY: usize = usize::MAX;
+
+// Number of decimated samples that Decimated buffers on the stack before
+// sending them to the inner meter
+const DECIMATION_CHUNK_SIZE: usize = 64;
+
+impl<M: Meter> Decimated<M> {
+    // Set up a meter with default settings which only sees one sample out of
+    // "factor", for audio data at a given sampling rate
+    //
+    // The factor must divide the sampling rate, so that the inner meter is
+    // configured for the exact sampling rate of the samples that it sees.
+    //
+    pub fn new(sampling_rate: u32, factor: u32) -> Self {
+        assert!(factor >= 1, "Decimation factor must be at least 1");
+        assert_eq!(sampling_rate % factor, 0,
+                   "Decimation factor must divide the sampling rate");
+        Self {
+            inner: M::new_for(sampling_rate / factor),
+            factor: factor as usize,
+            offset: Atomic::new(0),
+        }
+    }
+
+    // Query the decimation factor
+    pub fn factor(&self) -> u32 {
+        self.factor as u32
+    }
+
+    // Access the inner meter, e.g. for meter-specific functionality
+    //
+    // Beware that anything which depends on the sampling rate must be
+    // configured with the reduced sampling rate of the inner meter.
+    //
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+}
+
+impl<M: Meter> Meter for Decimated<M> {
+    // Decimation must be opted into explicitly, so generic code gets a meter
+    // which sees every sample
+    fn new_for(sampling_rate: u32) -> Self {
+        Self::new(sampling_rate, 1)
+    }
+
+    // Feed every Nth sample of the audio data into the inner meter
+    //
+    // The decimation phase is carried over from one slice to the next, so
+    // slices need not be multiples of the decimation factor. However, this
+    // requires that a given meter is only fed by one thread at a time, which
+    // is enforced by panicking if two threads try to feed it concurrently.
+    //
+    fn integrate_slice(&self, data: &[Sample]) {
+        let offset = self.offset.swap(BUSY, Ordering::Acquire);
+        assert_ne!(offset, BUSY, "Decimated meters must be fed by one thread");
+        let mut chunk = [0.0; DECIMATION_CHUNK_SIZE];
+        let mut len = 0;
+        for &spl in data.iter().skip(offset).step_by(self.factor) {
+            chunk[len] = spl;
+            len += 1;
+            if len == DECIMATION_CHUNK_SIZE {
+                self.inner.integrate_slice(&chunk[..]);
+                len = 0;
+            }
+        }
+        if len > 0 { self.inner.integrate_slice(&chunk[..len]); }
+        let next_offset = if offset >= data.len() {
+            offset - data.len()
+        } else {
+            let remainder = (data.len() - offset) % self.factor;
+            (self.factor - remainder) % self.factor
+        };
+        self.offset.store(next_offset, Ordering::Release);
+    }
+
+    // Read the inner meter
+    fn read(&self) -> Decibel {
+        self.inner.read()
+    }
+
+    // Reset the inner meter and the decimation phase
+    //
+    // Like feeding the meter, this must not be done concurrently with it.
+    //
+    fn reset(&self) {
+        self.inner.reset();
+        let offset = self.offset.swap(0, Ordering::Relaxed);
+        assert_ne!(offset, BUSY, "Decimated meters must be fed by one thread");
+    }
+
+    // Report the inner meter's configuration, including its reduced sampling
+    // rate, so that displays can tell that decimation is in effect
+    fn config(&self) -> MeterConfig {
+        self.inner.config()
+    }
+}
+
+// FIXME: Atomic crate should do this for me
+impl<M: Meter + UnwindSafe> UnwindSafe for Decimated<M> {}
+impl<M: Meter + RefUnwindSafe> RefUnwindSafe for Decimated<M> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sampling rate used by the tests
+    const SAMPLING_RATE: u32 = 48_000;
+
+    // Generate a 1 kHz sine wave with a given amplitude (in dBFS) and
+    // duration (in seconds)
+    fn sine(amplitude: Decibel, duration: f64) -> Vec<Sample> {
+        let amplitude = 10.0f64.powf(amplitude as f64 / 20.0);
+        let omega = 2.0 * std::f64::consts::PI * 1000.0
+                    / (SAMPLING_RATE as f64);
+        let len = (duration * SAMPLING_RATE as f64) as usize;
+        (0..len).map(|i| (amplitude * (omega * i as f64).sin()) as Sample)
+                .collect()
+    }
+
+    // Check that a reading is within some tolerance of the expected value
+    fn assert_close(measured: Decibel, expected: Decibel, tolerance: Decibel) {
+        assert!((measured - expected).abs() <= tolerance,
+                "Read {} dB, expected {} dB", measured, expected);
+    }
+
+    #[test]
+    fn vu_amplitude_correction() {
+        // The average absolute value of a full-scale square wave is 1, so it
+        // reads 0 dBFS without correction, but the sine corrections assume an
+        // average of 2/pi of the amplitude and thus overestimate it
+        let period = (0..48).map(|i| if i < 24 { 1.0 } else { -1.0 });
+        let square = period.cycle()
+                           .take(SAMPLING_RATE as usize)
+                           .collect::<Vec<Sample>>();
+        let read = |correction| {
+            let meter = VUMeter::new(SAMPLING_RATE, correction);
+            meter.integrate(square.iter().copied());
+            meter.read()
+        };
+        let half_pi = std::f32::consts::FRAC_PI_2;
+        assert_close(read(AmplitudeCorrection::None), 0.0, 0.01);
+        assert_close(read(AmplitudeCorrection::SineAmplitude),
+                     20.0 * half_pi.log10(),
+                     0.01);
+        assert_close(read(AmplitudeCorrection::SineRms),
+                     20.0 * half_pi.log10() - 10.0 * 2.0f32.log10(),
+                     0.01);
+    }
+
+    #[test]
+    fn peak_hold_above_current_peak() {
+        // Feed buffers of varying levels while another thread keeps reading
+        // the meter: the held peak must never be below the current one
+        let meter = SamplePeakMeter::new();
+        let levels = [-20.0, -6.0, -30.0, -12.0, -1.0, -40.0];
+        let buffers = levels.iter()
+                            .map(|&level| sine(level, 0.001))
+                            .collect::<Vec<_>>();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for buffer in buffers.iter().cycle().take(10_000) {
+                    meter.integrate_slice(buffer);
+                }
+            });
+            for _ in 0..10_000 {
+                let (current, held) = meter.read_and_reset_with_hold();
+                assert!(held >= current, "Held {} < current {}", held, current);
+            }
+        });
+    }
+
+    #[test]
+    fn vu_ballistics_at_192khz() {
+        // The weight should be accurate even though it is very close to 1
+        const HIGH_RATE: u32 = 192_000;
+        let tau = -0.3 / 0.01f64.ln();
+        let expected_weight = (-1.0 / (HIGH_RATE as f64) / tau).exp();
+        let weight = VUMeter::vu_weight(HIGH_RATE) as f64;
+        assert!(((1.0 - weight) / (1.0 - expected_weight) - 1.0).abs() < 1e-3,
+                "Weight is {}, expected {}", weight, expected_weight);
+
+        // A level step should take 300ms to get within 1% of its final value
+        let meter = VUMeter::new(HIGH_RATE, AmplitudeCorrection::None);
+        let step = |duration: f64| {
+            vec![0.5; (duration * HIGH_RATE as f64).round() as usize]
+        };
+        let progress = |meter: &VUMeter| {
+            10.0f32.powf(meter.read() / 20.0) / 0.5
+        };
+        meter.integrate(step(0.29));
+        assert!(progress(&meter) < 0.99);
+        meter.integrate(step(0.01));
+        assert!((progress(&meter) - 0.99).abs() < 1e-3);
+        meter.integrate(step(0.01));
+        assert!(progress(&meter) > 0.99);
+    }
+
+    #[test]
+    fn sample_peak_reading() {
+        // A -6 dBFS sine reads -6 dBFS, until the meter is reset by a read
+        let meter = SamplePeakMeter::new_for(SAMPLING_RATE);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        meter.integrate_slice(&sine(-6.0, 0.01));
+        assert_close(meter.read(), -6.0, 0.01);
+        assert_close(meter.read_and_reset(), -6.0, 0.01);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+    }
+
+    // Build one meter of each kind which supports concurrent feeding, with
+    // default settings
+    fn concurrent_meters() -> Vec<Box<dyn Meter + Sync>> {
+        vec![
+            Box::new(SamplePeakMeter::new_for(SAMPLING_RATE)),
+            Box::new(QuasiPeakMeter::new_for(SAMPLING_RATE)),
+            Box::new(VUMeter::new_for(SAMPLING_RATE)),
+            Box::new(RmsMeter::new_for(SAMPLING_RATE)),
+        ]
+    }
+
+    #[test]
+    fn heterogeneous_meters() {
+        // Feed the same buffer into meters of every kind
+        let mut meters = concurrent_meters();
+        meters.push(Box::new(IntegratedRmsMeter::new_for(SAMPLING_RATE)));
+        meters.push(Box::new(Decimated::<RmsMeter>::new(SAMPLING_RATE, 4)));
+        let tone = sine(-20.0, 1.0);
+        for meter in &meters {
+            meter.integrate_slice(&tone);
+        }
+
+        // Every meter should read somewhere between the RMS level and the
+        // amplitude of the sine, until it is reset
+        for meter in &meters {
+            let level = meter.read();
+            assert!(level > -23.5 && level < -19.5,
+                    "{:?} read {} dB", meter.config(), level);
+            meter.reset();
+            assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        }
+    }
+
+    #[test]
+    fn concurrent_feeding() {
+        // Feed the same buffer into some meters from several threads at once,
+        // so that the outcome does not depend on the order of the updates
+        const THREADS: usize = 4;
+        const BUFFERS: usize = 1000;
+        let buffer = sine(-20.0, 0.001);
+        let concurrent = concurrent_meters();
+        std::thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| {
+                    for _ in 0..BUFFERS {
+                        for meter in &concurrent {
+                            meter.integrate_slice(&buffer);
+                        }
+                    }
+                });
+            }
+        });
+
+        // No update should have been lost
+        let sequential = concurrent_meters();
+        for _ in 0..THREADS * BUFFERS {
+            for meter in &sequential {
+                meter.integrate_slice(&buffer);
+            }
+        }
+        for (concurrent, sequential) in concurrent.iter().zip(&sequential) {
+            assert_eq!(concurrent.read(), sequential.read(),
+                       "{:?} lost updates", concurrent.config());
+        }
+    }
+
+    #[test]
+    fn peak_hold_until_reset() {
+        // Once a loud peak went through, quieter audio and repeated reads
+        // don't lower the held peak...
+        let meter = SamplePeakMeter::new();
+        meter.integrate(sine(-3.0, 0.01));
+        assert_close(meter.read_and_reset(), -3.0, 0.01);
+        for _ in 0..10 {
+            meter.integrate(sine(-20.0, 0.01));
+            assert_close(meter.read_and_reset(), -20.0, 0.01);
+            assert_close(meter.read_hold(), -3.0, 0.01);
+        }
+
+        // ...until the hold is reset, which brings it down to the current peak
+        meter.integrate(sine(-20.0, 0.01));
+        meter.reset_hold();
+        assert_close(meter.read_hold(), -20.0, 0.01);
+        meter.read_and_reset();
+        meter.integrate(sine(-30.0, 0.01));
+        assert_close(meter.read_hold(), -20.0, 0.01);
+    }
+
+    #[test]
+    fn uniform_construction() {
+        // Every meter can be built from the sampling rate alone, and then
+        // starts out silent with its default settings at that sampling rate
+        fn build<M: Meter>() -> MeterConfig {
+            let meter = M::new_for(SAMPLING_RATE);
+            assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+            meter.config()
+        }
+        assert_eq!(build::<SamplePeakMeter>(),
+                   MeterConfig::SamplePeak {
+                       polarity: PeakPolarity::default(),
+                   });
+        assert_eq!(build::<QuasiPeakMeter>(),
+                   MeterConfig::QuasiPeak {
+                       sampling_rate: SAMPLING_RATE,
+                       attack_time: QuasiPeakMeter::DEFAULT_ATTACK_TIME,
+                   });
+        assert_eq!(build::<VUMeter>(),
+                   MeterConfig::VU {
+                       sampling_rate: SAMPLING_RATE,
+                       correction: AmplitudeCorrection::default(),
+                       reference: 0.0,
+                       peak_hold: PeakHold::default(),
+                   });
+        assert_eq!(build::<RmsMeter>(),
+                   MeterConfig::Rms {
+                       sampling_rate: SAMPLING_RATE,
+                       time_weighting: TimeWeighting::default(),
+                   });
+        assert_eq!(build::<IntegratedRmsMeter>(),
+                   MeterConfig::IntegratedRms {
+                       sampling_rate: SAMPLING_RATE,
+                       reference: 0.0,
+                   });
+        assert_eq!(build::<Decimated<RmsMeter>>(), build::<RmsMeter>());
+
+        // The zero-crossing rate meter is not a level meter, so it starts at
+        // zero crossings per second instead of -inf dB
+        let zcr = ZeroCrossingMeter::new_for(SAMPLING_RATE);
+        assert_eq!(Meter::read(&zcr), 0.0);
+        assert_eq!(zcr.config(),
+                   MeterConfig::ZeroCrossing { sampling_rate: SAMPLING_RATE });
+    }
+
+    #[test]
+    fn rms_time_weightings() {
+        // Whatever the time weighting, a steady sine eventually reads 3.01 dB
+        // below its amplitude. Once it is switched off, the reading falls by
+        // a factor of e after one falling time constant, i.e. by 4.34 dB.
+        let steady = -20.0 + 10.0 * 0.5f32.log10();
+        let fall = -10.0 * std::f32::consts::E.log10();
+        for &(weighting, tau) in &[(TimeWeighting::Fast, 0.125),
+                                   (TimeWeighting::Slow, 1.0),
+                                   (TimeWeighting::Impulse, 1.5)] {
+            let meter = RmsMeter::new(SAMPLING_RATE, weighting);
+            meter.integrate(sine(-20.0, 10.0));
+            assert_close(meter.read(), steady, 0.02);
+            let silence = (tau * SAMPLING_RATE as f64) as usize;
+            meter.integrate(vec![0.0; silence]);
+            assert_close(meter.read(), steady + fall, 0.05);
+        }
+    }
+
+    #[test]
+    fn vu_peak_hold() {
+        // On a step input, the peak hold overlay follows the VU value up...
+        let meter = VUMeter::new_for(SAMPLING_RATE);
+        meter.set_peak_hold(PeakHold { hold_time: 0.5, release_rate: 20.0 });
+        meter.integrate(sine(-20.0, 1.0));
+        assert_close(meter.read_peak_hold(), -20.0, 0.05);
+        assert!(meter.read_peak_hold() >= meter.read());
+
+        // ...then holds it while the VU value falls back...
+        let silence = |duration: f64| {
+            vec![0.0; (duration * SAMPLING_RATE as f64) as usize]
+        };
+        meter.integrate(silence(0.45));
+        assert!(meter.read() < -40.0);
+        assert_close(meter.read_peak_hold(), -20.0, 0.05);
+
+        // ...and releases it at the configured rate after the hold time
+        meter.integrate(silence(0.55));
+        assert_close(meter.read_peak_hold(), -30.0, 0.1);
+        meter.integrate(silence(0.5));
+        assert_close(meter.read_peak_hold(), -40.0, 0.1);
+
+        // A new peak is held again, and resets clear the overlay
+        meter.integrate(sine(-10.0, 1.0));
+        meter.integrate(silence(0.45));
+        assert_close(meter.read_peak_hold(), -10.0, 0.05);
+        meter.reset();
+        assert_eq!(meter.read_peak_hold(), Decibel::NEG_INFINITY);
+    }
+
+    #[test]
+    fn vu_reference_level() {
+        // With the usual -18 dBFS alignment, a -18 dBFS sine reads 0 VU, and
+        // the meter is offset by the same amount at other levels
+        let meter = VUMeter::new_for(SAMPLING_RATE);
+        meter.set_reference(-18.0);
+        assert_eq!(meter.reference(), -18.0);
+        meter.integrate(sine(-18.0, 1.0));
+        assert_close(meter.read(), 0.0, 0.05);
+        meter.reset();
+        meter.integrate(sine(-8.0, 1.0));
+        assert_close(meter.read(), 10.0, 0.05);
+    }
+
+    #[test]
+    fn meter_configs() {
+        // Meters report the parameters that they were set up with, including
+        // those which were changed after construction
+        let peak = SamplePeakMeter::with_polarity(PeakPolarity::Negative);
+        assert_eq!(peak.config(),
+                   MeterConfig::SamplePeak {
+                       polarity: PeakPolarity::Negative,
+                   });
+        let quasi_peak = QuasiPeakMeter::new(SAMPLING_RATE, 0.005);
+        assert_eq!(quasi_peak.config(),
+                   MeterConfig::QuasiPeak {
+                       sampling_rate: SAMPLING_RATE,
+                       attack_time: 0.005,
+                   });
+        let vu = VUMeter::new(SAMPLING_RATE, AmplitudeCorrection::SineRms);
+        vu.set_reference(-20.0);
+        let peak_hold = PeakHold { hold_time: 1.0, release_rate: 10.0 };
+        vu.set_peak_hold(peak_hold);
+        assert_eq!(vu.config(),
+                   MeterConfig::VU {
+                       sampling_rate: SAMPLING_RATE,
+                       correction: AmplitudeCorrection::SineRms,
+                       reference: -20.0,
+                       peak_hold,
+                   });
+        let rms = RmsMeter::new(SAMPLING_RATE, TimeWeighting::Slow);
+        rms.update_sampling_rate(96_000);
+        assert_eq!(rms.config(),
+                   MeterConfig::Rms {
+                       sampling_rate: 96_000,
+                       time_weighting: TimeWeighting::Slow,
+                   });
+        let integrated = IntegratedRmsMeter::new(SAMPLING_RATE);
+        integrated.set_reference(-23.0);
+        assert_eq!(integrated.config(),
+                   MeterConfig::IntegratedRms {
+                       sampling_rate: SAMPLING_RATE,
+                       reference: -23.0,
+                   });
+        let zcr = ZeroCrossingMeter::new(SAMPLING_RATE);
+        assert_eq!(zcr.config(),
+                   MeterConfig::ZeroCrossing { sampling_rate: SAMPLING_RATE });
+    }
+
+    #[test]
+    fn integrated_rms_steady_tone() {
+        // RMS level of a -20 dBFS sine
+        let rms = -20.0 + 10.0 * 0.5f32.log10();
+
+        // Nothing is measured until a first block is complete
+        let meter = IntegratedRmsMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-20.0, 0.39));
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+
+        // From then on, a steady tone reads as its RMS level
+        meter.integrate(sine(-20.0, 0.01));
+        assert_close(meter.read(), rms, 0.01);
+        meter.integrate(sine(-20.0, 10.0));
+        assert_close(meter.read(), rms, 0.01);
+
+        // Resetting the meter starts a new session
+        meter.reset();
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+    }
+
+    #[test]
+    fn integrated_rms_gate() {
+        // Silent passages do not drag the level down...
+        let rms = -20.0 + 10.0 * 0.5f32.log10();
+        let meter = IntegratedRmsMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-20.0, 2.0));
+        meter.integrate(vec![0.0; 10 * SAMPLING_RATE as usize]);
+        meter.integrate(sine(-80.0, 2.0));
+        assert_close(meter.read(), rms, 0.01);
+
+        // ...but quieter passages above the gate do, every block having the
+        // same weight in the average
+        meter.integrate(sine(-40.0, 2.0));
+        let loud_ms = 10.0f32.powf(rms / 10.0);
+        let quiet_ms = 10.0f32.powf((rms - 20.0) / 10.0);
+        let mean_square = (loud_ms + quiet_ms) / 2.0;
+        assert_close(meter.read(), 10.0 * mean_square.log10(), 0.01);
+    }
+
+    #[test]
+    fn vu_sanitization() {
+        // Huge samples are clamped, so the meter recovers from them
+        let meter = VUMeter::new_for(SAMPLING_RATE);
+        meter.integrate(vec![1e30]);
+        assert_eq!(meter.sanitized_events(), 1);
+        assert!(meter.read().is_finite());
+        meter.integrate(sine(-6.0, 1.0));
+        assert_close(meter.read(), -6.0, 0.1);
+
+        // NaN samples are treated as silence, instead of kicking the reading
+        // upwards
+        let before = meter.read();
+        let mut vu = meter.begin_batch();
+        meter.integrate_batch(&mut vu, vec![Sample::NAN; 10]);
+        meter.end_batch(vu);
+        assert_eq!(meter.sanitized_events(), 2);
+        assert!(meter.read() <= before);
+        meter.integrate(sine(-6.0, 1.0));
+        assert_close(meter.read(), -6.0, 0.1);
+    }
+
+    #[test]
+    fn sample_peak_state_round_trip() {
+        let meter = SamplePeakMeter::new();
+        meter.integrate(sine(-6.0, 0.01));
+        meter.read_and_reset();
+        meter.integrate(sine(-12.0, 0.01));
+        let restored = SamplePeakMeter::new();
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-9.0, 0.01);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.read_and_reset_with_hold(),
+                   meter.read_and_reset_with_hold());
+
+        // The held peak is never restored below the window peak
+        restored.restore_state(SamplePeakState { window: 0.5, held: 0.25 });
+        assert_eq!(restored.serialize_state(),
+                   SamplePeakState { window: 0.5, held: 0.5 });
+    }
+
+    #[test]
+    fn vu_state_round_trip() {
+        let meter = VUMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 0.1));
+        let restored = VUMeter::new_for(SAMPLING_RATE);
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-12.0, 0.1);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.read(), meter.read());
+    }
+
+    #[test]
+    fn rms_state_round_trip() {
+        let meter = RmsMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 0.1));
+        let restored = RmsMeter::new_for(SAMPLING_RATE);
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-12.0, 0.1);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.read(), meter.read());
+    }
+
+    #[test]
+    fn integrated_rms_state_round_trip() {
+        // Save the state in the middle of a block
+        let meter = IntegratedRmsMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 1.0));
+        let restored = IntegratedRmsMeter::new_for(SAMPLING_RATE);
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-12.0, 1.0);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.read(), meter.read());
+        assert_eq!(restored.serialize_state(), meter.serialize_state());
+    }
+
+    #[test]
+    fn zero_crossing_rate() {
+        // A 1 kHz sine crosses zero 2000 times per second
+        let meter = ZeroCrossingMeter::new(SAMPLING_RATE);
+        meter.integrate(sine(-20.0, 2.0));
+        let rate = meter.rate_hz();
+        assert!((rate - 2000.0).abs() < 20.0, "Read {} crossings/s", rate);
+        assert_eq!(meter.config(),
+                   MeterConfig::ZeroCrossing { sampling_rate: SAMPLING_RATE });
+
+        // Zeros of either sign count as positive, so touching zero from
+        // above is not a crossing, no matter how it is batched...
+        meter.reset();
+        let touching = [1.0, 0.0, -0.0, 1.0, 0.0, 1.0];
+        let touching = touching.iter().copied().cycle().take(48_000);
+        meter.integrate(touching.clone());
+        assert_eq!(meter.rate_hz(), 0.0);
+        let mut state = meter.begin_batch();
+        for _ in 0..10 {
+            meter.integrate_batch(&mut state, touching.clone().take(7));
+        }
+        meter.end_batch(state);
+        assert_eq!(meter.rate_hz(), 0.0);
+
+        // ...but going from below zero to zero is, and so is going back
+        let through = [-1.0, 0.0, -1.0, -0.0];
+        meter.integrate(through.iter().copied().cycle().take(96_000));
+        let rate = meter.rate_hz();
+        let expected = SAMPLING_RATE as f32;
+        assert!((rate - expected).abs() < 0.01 * expected,
+                "Read {} crossings/s", rate);
+    }
+
+    #[test]
+    fn zero_crossing_state_round_trip() {
+        let meter = ZeroCrossingMeter::new(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 0.1));
+        let restored = ZeroCrossingMeter::new(SAMPLING_RATE);
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-12.0, 0.1);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.rate_hz(), meter.rate_hz());
+
+        // Invalid states are not restored
+        restored.restore_state(ZeroCrossingState {
+            crossings_per_sample: Sample::NAN,
+            last_sign: 1.0,
+        });
+        assert_eq!(restored.serialize_state(), ZeroCrossingState::default());
+    }
+
+    #[test]
+    fn quasi_peak_ballistics() {
+        // A single-sample spike barely registers...
+        let meter = QuasiPeakMeter::new_for(SAMPLING_RATE);
+        let mut spike = vec![0.0; 480];
+        spike[240] = 1.0;
+        meter.integrate(spike);
+        assert!(meter.read_and_reset() < -30.0);
+
+        // ...but a sustained level does
+        meter.integrate(vec![1.0; 480]);
+        assert_close(meter.read_and_reset(), 0.0, 0.01);
+
+        // The window peak is reset by reads, but the envelope is not
+        meter.integrate(vec![0.0]);
+        assert_close(meter.read_and_reset(), 0.0, 0.01);
+    }
+
+    #[test]
+    fn quasi_peak_state_round_trip() {
+        let meter = QuasiPeakMeter::new_for(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 0.01));
+        let restored = QuasiPeakMeter::new_for(SAMPLING_RATE);
+        restored.restore_state(meter.serialize_state());
+        let tail = sine(-12.0, 0.01);
+        meter.integrate(tail.iter().copied());
+        restored.integrate(tail.iter().copied());
+        assert_eq!(restored.read_and_reset(), meter.read_and_reset());
+        assert_eq!(restored.serialize_state(), meter.serialize_state());
+    }
+
+    #[test]
+    fn decimated_sine_error() {
+        // Decimating a 1 kHz sine by 12 at 48 kHz leaves 4 samples per period,
+        // which may all miss the peaks by 45 degrees, or hit them
+        const FACTOR: u32 = 12;
+        let tone = sine(-6.0, 1.0);
+        let full_rate = RmsMeter::new_for(SAMPLING_RATE);
+        full_rate.integrate_slice(&tone);
+        let worst_error = 20.0 * (std::f32::consts::PI / 4.0).cos().log10();
+        for phase in 0..FACTOR as usize {
+            let peak = Decimated::<SamplePeakMeter>::new(SAMPLING_RATE,
+                                                         FACTOR);
+            peak.integrate_slice(&tone[phase..]);
+            let error = peak.read() + 6.0;
+            assert!(error <= 0.01 && error >= worst_error - 0.01,
+                    "Peak is off by {} dB at phase {}", error, phase);
+            if phase == FACTOR as usize / 2 {
+                assert_close(error, worst_error, 0.01);
+            }
+
+            // The mean square of 4 evenly spaced sine samples is exact, so
+            // the RMS meter is barely affected
+            let rms = Decimated::<RmsMeter>::new(SAMPLING_RATE, FACTOR);
+            rms.integrate_slice(&tone[phase..]);
+            assert_close(rms.read(), full_rate.read(), 0.1);
+        }
+    }
+
+    #[test]
+    fn decimated_uneven_slices() {
+        // Feed a ramp in slices whose lengths are not multiples of the factor
+        const FACTOR: u32 = 5;
+        let ramp = (0..1000).map(|i| i as Sample / 1000.0)
+                            .collect::<Vec<_>>();
+        let sliced = Decimated::<RmsMeter>::new(SAMPLING_RATE, FACTOR);
+        let mut start = 0;
+        for &len in [7, 13, 1, 0, 2, 29, 4].iter().cycle() {
+            let end = (start + len).min(ramp.len());
+            sliced.integrate_slice(&ramp[start..end]);
+            let expected_offset =
+                (FACTOR as usize - end % FACTOR as usize) % FACTOR as usize;
+            assert_eq!(sliced.offset.load(Ordering::Relaxed),
+                       expected_offset);
+            start = end;
+            if start == ramp.len() { break; }
+        }
+
+        // The inner meter should have seen the same samples as if the ramp
+        // had been fed at once
+        let whole = Decimated::<RmsMeter>::new(SAMPLING_RATE, FACTOR);
+        whole.integrate_slice(&ramp);
+        assert_eq!(sliced.inner().serialize_state(),
+                   whole.inner().serialize_state());
+    }
+
+    #[test]
+    fn decimated_misuse() {
+        // Factors which do not divide the sampling rate are rejected, so that
+        // the inner meter always gets the exact reduced sampling rate
+        let new = |factor| {
+            std::panic::catch_unwind(|| {
+                Decimated::<RmsMeter>::new(SAMPLING_RATE, factor)
+            })
+        };
+        assert!(new(7).is_err());
+        let meter = new(3).unwrap();
+        assert_eq!(meter.inner().sampling_rate(), 16_000);
+
+        // So is feeding a meter while another thread is feeding it
+        meter.offset.store(BUSY, Ordering::Relaxed);
+        let feed = std::panic::catch_unwind(|| {
+            meter.integrate_slice(&[0.0; 8])
+        });
+        assert!(feed.is_err());
+    }
+
+    #[test]
+    fn peak_polarity() {
+        // An asymmetrical signal, with large negative excursions and small
+        // positive ones
+        let signal = [0.1, -0.8, 0.25, -0.5, 0.0];
+        let read = |polarity| {
+            let meter = SamplePeakMeter::with_polarity(polarity);
+            meter.integrate(signal.iter().copied());
+            meter.read()
+        };
+        assert_eq!(read(PeakPolarity::Positive), 20.0 * 0.25f32.log10());
+        assert_eq!(read(PeakPolarity::Negative), 20.0 * 0.8f32.log10());
+        assert_eq!(read(PeakPolarity::Absolute), 20.0 * 0.8f32.log10());
+    }
+
+    #[test]
+    fn sample_peak_sanitization() {
+        // Non-finite samples are skipped, without discarding the real peaks
+        // which share their buffer
+        let meter = SamplePeakMeter::new();
+        meter.integrate(vec![0.5, Sample::INFINITY, 0.25, Sample::NAN]);
+        assert_eq!(meter.sanitized_events(), 2);
+        assert_eq!(meter.read_with_hold(),
+                   (20.0 * 0.5f32.log10(), 20.0 * 0.5f32.log10()));
+        meter.integrate(sine(-6.0, 0.01));
+        assert_close(meter.read_and_reset(), -6.0, 0.01);
+
+        // The same goes for batched integration
+        let mut max = meter.begin_batch();
+        meter.integrate_batch(&mut max, sine(-12.0, 0.01));
+        meter.integrate_batch(&mut max, vec![Sample::NEG_INFINITY, 0.125]);
+        meter.end_batch(max);
+        assert_eq!(meter.sanitized_events(), 3);
+        assert_close(meter.read_and_reset(), -12.0, 0.01);
+        assert_close(meter.read_hold(), -6.0, 0.01);
+    }
+
+    #[test]
+    fn quasi_peak_sanitization() {
+        let meter = QuasiPeakMeter::new_for(SAMPLING_RATE);
+        meter.integrate(vec![Sample::NAN]);
+        assert_eq!(meter.sanitized_events(), 1);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        meter.integrate(sine(-6.0, 0.1));
+        assert!(meter.read_and_reset() > -7.0);
+
+        // Batched integration must not publish the non-finite envelope
+        let mut state = meter.begin_batch();
+        meter.integrate_batch(&mut state, vec![Sample::INFINITY]);
+        meter.end_batch(state);
+        assert_eq!(meter.sanitized_events(), 2);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        let mut state = meter.begin_batch();
+        meter.integrate_batch(&mut state, sine(-6.0, 0.1));
+        meter.end_batch(state);
+        assert!(meter.read() > -7.0);
+    }
+
+    #[test]
+    fn rms_sanitization() {
+        // RMS level of a -6 dBFS sine
+        let rms = -6.0 + 10.0 * 0.5f32.log10();
+
+        let meter = RmsMeter::new_for(SAMPLING_RATE);
+        meter.integrate(vec![Sample::INFINITY]);
+        assert_eq!(meter.sanitized_events(), 1);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        meter.integrate(sine(-6.0, 2.0));
+        assert_close(meter.read(), rms, 0.1);
+
+        // Batched integration must not publish the non-finite mean square
+        let mut ms = meter.begin_batch();
+        meter.integrate_batch(&mut ms, vec![Sample::NAN]);
+        meter.end_batch(ms);
+        assert_eq!(meter.sanitized_events(), 2);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+        let mut ms = meter.begin_batch();
+        meter.integrate_batch(&mut ms, sine(-6.0, 2.0));
+        meter.end_batch(ms);
+        assert_close(meter.read(), rms, 0.1);
+
+        // Non-finite states are not restored either
+        meter.restore_state(RmsState { mean_square: Sample::INFINITY,
+                                       level: 0.0 });
+        assert_eq!(meter.sanitized_events(), 3);
+        assert_eq!(meter.read(), Decibel::NEG_INFINITY);
+    }
+
+    #[test]
+    fn integrated_rms_sanitization() {
+        // RMS level of a -6 dBFS sine
+        let rms = -6.0 + 10.0 * 0.5f32.log10();
+
+        // Only the block which contains the infinite sample is discarded
+        let meter = IntegratedRmsMeter::new(SAMPLING_RATE);
+        meter.integrate(sine(-6.0, 1.2));
+        meter.integrate(vec![Sample::INFINITY]);
+        meter.integrate(sine(-6.0, 0.4));
+        assert_eq!(meter.sanitized_events(), 1);
+        assert_close(meter.read(), rms, 0.05);
+
+        // Same with batched integration
+        let mut batch = meter.begin_batch();
+        meter.integrate_batch(&mut batch, vec![Sample::NAN]);
+        meter.integrate_batch(&mut batch, sine(-6.0, 0.8));
+        meter.end_batch(batch);
+        assert_eq!(meter.sanitized_events(), 2);
+        assert_close(meter.read(), rms, 0.05);
+    }
+
+    #[test]
+    fn dialogue_reference() {
+        // Nothing can be captured before a level has been measured
+        let meter = IntegratedRmsMeter::new_for(SAMPLING_RATE);
+        assert_eq!(meter.capture_reference(), None);
+        assert_eq!(meter.reference(), 0.0);
+
+        // Capture the level of a dialogue passage...
+        meter.integrate(sine(-26.0, 5.0));
+        let dialogue = meter.capture_reference().unwrap();
+        assert_close(dialogue, -26.0 + 10.0 * 0.5f32.log10(), 0.01);
+        assert_eq!(meter.reference(), dialogue);
+        assert_close(meter.read_relative(), 0.0, 0.001);
+
+        // ...then measure the rest of the program relative to it
+        meter.reset();
+        meter.integrate(sine(-20.0, 5.0));
+        assert_eq!(meter.reference(), dialogue);
+        assert_close(meter.read_relative(), 6.0, 0.01);
+    }
+}
\ No newline at end of file
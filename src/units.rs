@@ -0,0 +1,109 @@
+use crate::{Sample, Time};
+
+
+// Conversions from integer PCM samples to our floating-point samples
+//
+// Integer samples are normalized by dividing them by 2^(bits - 1). This way,
+// the most negative integer maps to exactly -1.0, and the most positive integer
+// maps to slightly less than 1.0. This preserves the asymmetry of two's
+// complement instead of distorting the signal to make it symmetrical, and is
+// the convention followed by most audio software.
+//
+// Since meters accept iterators of samples, these can be used to feed them with
+// integer PCM data, e.g. meter.integrate(data.iter().copied().map(from_i16)).
+
+// Convert a 16-bit integer PCM sample
+pub fn from_i16(sample: i16) -> Sample {
+    Sample::from(sample) / 32_768.0
+}
+
+// Convert a 24-bit integer PCM sample, stored in the low bits of an i32
+pub fn from_i24(sample: i32) -> Sample {
+    debug_assert!((-(1 << 23)..(1 << 23)).contains(&sample),
+                  "Input is not a 24-bit integer sample");
+    sample as Sample / 8_388_608.0
+}
+
+// Convert a 32-bit integer PCM sample
+//
+// Single-precision samples cannot represent every 32-bit integer exactly, so
+// the most positive integers are rounded up to exactly 1.0.
+//
+pub fn from_i32(sample: i32) -> Sample {
+    sample as Sample / 2_147_483_648.0
+}
+
+
+// Conversions between durations in seconds, JACK clock durations (in µs, see
+// Time) and numbers of audio frames
+//
+// Frame counts measure audio time, whereas the JACK clock measures wall clock
+// time. The two usually match, but not when JACK is freewheeling, as audio
+// frames are then processed as fast as possible. Measurement durations should
+// thus be computed from frame counts, and timeouts from the JACK clock.
+
+// Convert a JACK clock duration to seconds
+pub fn usecs_to_secs(usecs: Time) -> f64 {
+    usecs as f64 / 1_000_000.0
+}
+
+// Convert a duration in seconds to a JACK clock duration, rounding to the
+// nearest microsecond. Negative durations are clamped to zero.
+pub fn secs_to_usecs(secs: f64) -> Time {
+    (secs * 1_000_000.0).round() as Time
+}
+
+// Convert a number of audio frames to seconds, at a given sampling rate
+pub fn frames_to_secs(frames: u64, sampling_rate: u32) -> f64 {
+    frames as f64 / sampling_rate as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meters::SamplePeakMeter;
+
+    #[test]
+    fn jack_clock_conversions() {
+        assert_eq!(usecs_to_secs(1_500_000), 1.5);
+        assert_eq!(secs_to_usecs(1.5), 1_500_000);
+        assert_eq!(secs_to_usecs(0.000_000_6), 1);
+        assert_eq!(secs_to_usecs(-1.0), 0);
+        for &usecs in &[0, 1, 999_999, 86_400_000_000] {
+            assert_eq!(secs_to_usecs(usecs_to_secs(usecs)), usecs);
+        }
+    }
+
+    #[test]
+    fn frame_conversions() {
+        for &sampling_rate in &[44_100, 48_000, 96_000, 192_000] {
+            let frames = 90 * sampling_rate as u64;
+            assert_eq!(frames_to_secs(frames, sampling_rate), 90.0);
+            assert_eq!(frames_to_secs(sampling_rate as u64 / 2,
+                                      sampling_rate),
+                       0.5);
+        }
+    }
+
+    #[test]
+    fn integer_samples() {
+        // The most negative integer maps to exactly -1.0...
+        assert_eq!(from_i16(i16::MIN), -1.0);
+        assert_eq!(from_i24(-(1 << 23)), -1.0);
+        assert_eq!(from_i32(i32::MIN), -1.0);
+
+        // ...and the most positive integer maps to slightly less than 1.0,
+        // except in 32-bit where that rounds to 1.0
+        assert!(from_i16(i16::MAX) < 1.0);
+        assert!(from_i24((1 << 23) - 1) < 1.0);
+        assert_eq!(from_i32(i32::MAX), 1.0);
+        assert_eq!(from_i16(0), 0.0);
+
+        // So full-scale integer PCM reads about 0 dBFS
+        let meter = SamplePeakMeter::new();
+        meter.integrate([i16::MAX, 0, i16::MAX].iter().copied().map(from_i16));
+        assert!(meter.read_and_reset().abs() < 0.001);
+        meter.integrate([i16::MIN, 0].iter().copied().map(from_i16));
+        assert_eq!(meter.read_and_reset(), 0.0);
+    }
+}
\ No newline at end of file